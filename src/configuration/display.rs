@@ -9,3 +9,11 @@ pub struct DisplayConfig {
     pub resolution: Vec2,
 }
 impl ConfigTag for DisplayConfig {}
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        return Self {
+            pos: IVec2::ZERO,
+            resolution: Vec2::new(1080.0, 480.0),
+        };
+    }
+}
@@ -1,9 +1,15 @@
-use std::str::FromStr;
+use std::{fs::File, io::BufReader, str::FromStr};
 
-use bevy::prelude::{KeyCode, Resource};
-use serde::{de::Visitor, Deserialize, Serialize};
+use bevy::{
+    log::info,
+    prelude::{KeyCode, Resource},
+};
+use serde::{
+    de::{Unexpected, Visitor},
+    ser, Deserialize, Serialize,
+};
 
-use super::ConfigTag;
+use super::{write_json, Config, ConfigError};
 
 #[derive(Resource, Serialize, Deserialize)]
 pub struct KeyBinds {
@@ -15,8 +21,184 @@ pub struct KeyBinds {
     pub reload_level: KeyBind,
     pub next_level: KeyBind,
     pub prev_level: KeyBind,
+    #[serde(default = "default_pause")]
+    pub pause: KeyBind,
+    #[serde(default = "default_editor")]
+    pub editor: KeyBind,
+    #[serde(default = "default_editor_tool")]
+    pub editor_tool: KeyBind,
+    #[serde(default = "default_editor_delete")]
+    pub editor_delete: KeyBind,
+    #[serde(default = "default_editor_rotate_left")]
+    pub editor_rotate_left: KeyBind,
+    #[serde(default = "default_editor_rotate_right")]
+    pub editor_rotate_right: KeyBind,
+    #[serde(default = "default_editor_material")]
+    pub editor_material: KeyBind,
+    #[serde(default = "default_editor_second_material")]
+    pub editor_second_material: KeyBind,
+    #[serde(default = "default_editor_deselect")]
+    pub editor_deselect: KeyBind,
+    #[serde(default = "default_editor_grow_width")]
+    pub editor_grow_width: KeyBind,
+    #[serde(default = "default_editor_shrink_width")]
+    pub editor_shrink_width: KeyBind,
+    #[serde(default = "default_editor_grow_height")]
+    pub editor_grow_height: KeyBind,
+    #[serde(default = "default_editor_shrink_height")]
+    pub editor_shrink_height: KeyBind,
+    #[serde(default = "default_editor_save")]
+    pub editor_save: KeyBind,
+}
+// Two actions on one key would both fire, so a file that does that is rejected like broken JSON
+impl Config for KeyBinds {
+    fn load_cfg(path: &str) -> Result<Self, ConfigError> {
+        info!("Loading config file at {path}");
+        let file = File::open(path).map_err(|err| ConfigError::io(path, err))?;
+        let key_binds: KeyBinds = serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| ConfigError::json(path, err))?;
+
+        if let Some((first, second, key_code)) = key_binds.duplicate() {
+            return Err(ConfigError::DuplicateKeyBind {
+                path: path.to_string(),
+                first: first.to_string(),
+                second: second.to_string(),
+                key: format!("{key_code:?}"),
+            });
+        }
+        return Ok(key_binds);
+    }
+
+    fn save_cfg(&self, path: &str) -> Result<(), ConfigError> {
+        info!("Saving config file at {path}");
+        return write_json(path, self);
+    }
+}
+impl Default for KeyBinds {
+    fn default() -> Self {
+        return Self {
+            up: KeyBind(KeyCode::KeyW),
+            left: KeyBind(KeyCode::KeyA),
+            down: KeyBind(KeyCode::KeyS),
+            right: KeyBind(KeyCode::KeyD),
+            time_shift: KeyBind(KeyCode::KeyE),
+            reload_level: KeyBind(KeyCode::KeyR),
+            next_level: KeyBind(KeyCode::KeyP),
+            prev_level: KeyBind(KeyCode::KeyO),
+            pause: default_pause(),
+            editor: default_editor(),
+            editor_tool: default_editor_tool(),
+            editor_delete: default_editor_delete(),
+            editor_rotate_left: default_editor_rotate_left(),
+            editor_rotate_right: default_editor_rotate_right(),
+            editor_material: default_editor_material(),
+            editor_second_material: default_editor_second_material(),
+            editor_deselect: default_editor_deselect(),
+            editor_grow_width: default_editor_grow_width(),
+            editor_shrink_width: default_editor_shrink_width(),
+            editor_grow_height: default_editor_grow_height(),
+            editor_shrink_height: default_editor_shrink_height(),
+            editor_save: default_editor_save(),
+        };
+    }
+}
+
+impl KeyBinds {
+    // Every action with its key, in file order
+    pub fn all(&self) -> Vec<(&'static str, KeyCode)> {
+        return vec![
+            ("up", self.up.0),
+            ("left", self.left.0),
+            ("down", self.down.0),
+            ("right", self.right.0),
+            ("time_shift", self.time_shift.0),
+            ("reload_level", self.reload_level.0),
+            ("next_level", self.next_level.0),
+            ("prev_level", self.prev_level.0),
+            ("pause", self.pause.0),
+            ("editor", self.editor.0),
+            ("editor_tool", self.editor_tool.0),
+            ("editor_delete", self.editor_delete.0),
+            ("editor_rotate_left", self.editor_rotate_left.0),
+            ("editor_rotate_right", self.editor_rotate_right.0),
+            ("editor_material", self.editor_material.0),
+            ("editor_second_material", self.editor_second_material.0),
+            ("editor_deselect", self.editor_deselect.0),
+            ("editor_grow_width", self.editor_grow_width.0),
+            ("editor_shrink_width", self.editor_shrink_width.0),
+            ("editor_grow_height", self.editor_grow_height.0),
+            ("editor_shrink_height", self.editor_shrink_height.0),
+            ("editor_save", self.editor_save.0),
+        ];
+    }
+
+    // The first two actions sharing a key
+    pub fn duplicate(&self) -> Option<(&'static str, &'static str, KeyCode)> {
+        let all = self.all();
+        for (i, (first, key_code)) in all.iter().enumerate() {
+            if let Some((second, _)) = all[i + 1..].iter().find(|(_, other)| other == key_code) {
+                return Some((*first, *second, *key_code));
+            }
+        }
+        return None;
+    }
+}
+
+fn default_pause() -> KeyBind {
+    return KeyBind(KeyCode::Escape);
+}
+
+fn default_editor() -> KeyBind {
+    return KeyBind(KeyCode::F1);
+}
+
+fn default_editor_tool() -> KeyBind {
+    return KeyBind(KeyCode::Tab);
+}
+
+fn default_editor_delete() -> KeyBind {
+    return KeyBind(KeyCode::Delete);
+}
+
+fn default_editor_rotate_left() -> KeyBind {
+    return KeyBind(KeyCode::KeyZ);
+}
+
+fn default_editor_rotate_right() -> KeyBind {
+    return KeyBind(KeyCode::KeyX);
+}
+
+fn default_editor_material() -> KeyBind {
+    return KeyBind(KeyCode::KeyM);
+}
+
+fn default_editor_second_material() -> KeyBind {
+    return KeyBind(KeyCode::KeyN);
+}
+
+fn default_editor_deselect() -> KeyBind {
+    return KeyBind(KeyCode::KeyC);
+}
+
+fn default_editor_grow_width() -> KeyBind {
+    return KeyBind(KeyCode::ArrowRight);
+}
+
+fn default_editor_shrink_width() -> KeyBind {
+    return KeyBind(KeyCode::ArrowLeft);
+}
+
+fn default_editor_grow_height() -> KeyBind {
+    return KeyBind(KeyCode::ArrowUp);
+}
+
+fn default_editor_shrink_height() -> KeyBind {
+    return KeyBind(KeyCode::ArrowDown);
+}
+
+fn default_editor_save() -> KeyBind {
+    return KeyBind(KeyCode::F2);
 }
-impl ConfigTag for KeyBinds {}
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseKeyBindError;
@@ -33,9 +215,23 @@ impl FromStr for KeyBind {
             "KeyE" => KeyCode::KeyE,
             "ArrowDown" => KeyCode::ArrowDown,
             "ArrowUp" => KeyCode::ArrowUp,
+            "ArrowLeft" => KeyCode::ArrowLeft,
+            "ArrowRight" => KeyCode::ArrowRight,
             "KeyR" => KeyCode::KeyR,
             "KeyP" => KeyCode::KeyP,
             "KeyO" => KeyCode::KeyO,
+            "Escape" => KeyCode::Escape,
+            "F1" => KeyCode::F1,
+            "F2" => KeyCode::F2,
+            "Tab" => KeyCode::Tab,
+            "Delete" => KeyCode::Delete,
+            "Backspace" => KeyCode::Backspace,
+            "KeyQ" => KeyCode::KeyQ,
+            "KeyC" => KeyCode::KeyC,
+            "KeyZ" => KeyCode::KeyZ,
+            "KeyX" => KeyCode::KeyX,
+            "KeyM" => KeyCode::KeyM,
+            "KeyN" => KeyCode::KeyN,
             _ => return Err(ParseKeyBindError),
         };
         return Ok(KeyBind(key_code));
@@ -54,10 +250,27 @@ impl Serialize for KeyBind {
             KeyCode::KeyE => serializer.serialize_str("KeyE"),
             KeyCode::ArrowDown => serializer.serialize_str("ArrowDown"),
             KeyCode::ArrowUp => serializer.serialize_str("ArrowUp"),
+            KeyCode::ArrowLeft => serializer.serialize_str("ArrowLeft"),
+            KeyCode::ArrowRight => serializer.serialize_str("ArrowRight"),
             KeyCode::KeyR => serializer.serialize_str("KeyR"),
             KeyCode::KeyP => serializer.serialize_str("KeyP"),
             KeyCode::KeyO => serializer.serialize_str("KeyO"),
-            _ => panic!("Unable to serialize KeyCode {:?}", self.0),
+            KeyCode::Escape => serializer.serialize_str("Escape"),
+            KeyCode::F1 => serializer.serialize_str("F1"),
+            KeyCode::F2 => serializer.serialize_str("F2"),
+            KeyCode::Tab => serializer.serialize_str("Tab"),
+            KeyCode::Delete => serializer.serialize_str("Delete"),
+            KeyCode::Backspace => serializer.serialize_str("Backspace"),
+            KeyCode::KeyQ => serializer.serialize_str("KeyQ"),
+            KeyCode::KeyC => serializer.serialize_str("KeyC"),
+            KeyCode::KeyZ => serializer.serialize_str("KeyZ"),
+            KeyCode::KeyX => serializer.serialize_str("KeyX"),
+            KeyCode::KeyM => serializer.serialize_str("KeyM"),
+            KeyCode::KeyN => serializer.serialize_str("KeyN"),
+            _ => Err(ser::Error::custom(format!(
+                "Unable to serialize KeyCode {:?}",
+                self.0
+            ))),
         }
     }
 }
@@ -87,7 +300,7 @@ impl<'de> Deserialize<'de> for KeyBind {
             {
                 match KeyBind::from_str(v) {
                     Ok(key_bind) => return Ok(key_bind),
-                    Err(err) => panic!("Failed to parse str {v}, {err:?}"),
+                    Err(_) => return Err(E::invalid_value(Unexpected::Str(v), &self)),
                 }
             }
         }
@@ -95,3 +308,36 @@ impl<'de> Deserialize<'de> for KeyBind {
         return Ok(deserializer.deserialize_string(KeyBindVisitor)?);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use bevy::prelude::KeyCode;
+
+    use super::{KeyBind, KeyBinds};
+    use crate::configuration::{Config, ConfigError};
+
+    #[test]
+    fn default_key_binds_are_unique() {
+        assert!(KeyBinds::default().duplicate().is_none());
+    }
+
+    #[test]
+    fn duplicate_key_binds_rejected() {
+        let mut key_binds = KeyBinds::default();
+        key_binds.editor_rotate_right = KeyBind(KeyCode::KeyE);
+        assert!(
+            key_binds.duplicate() == Some(("time_shift", "editor_rotate_right", KeyCode::KeyE))
+        );
+
+        let path = env::temp_dir()
+            .join("time_trial_duplicate_key_binds.json")
+            .to_string_lossy()
+            .to_string();
+        key_binds.save_cfg(&path).unwrap();
+        let loaded = KeyBinds::load_cfg(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(ConfigError::DuplicateKeyBind { .. })));
+    }
+}
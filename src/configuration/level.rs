@@ -6,8 +6,25 @@ use super::ConfigTag;
 #[derive(Resource, Deserialize, Serialize)]
 pub struct LevelConfig {
     pub dir: String,
+    #[serde(default = "default_packs_dir")]
+    pub packs_dir: String,
     pub cur_level: String,
     pub gen_on_start: bool,
     pub save_on_start: bool,
 }
 impl ConfigTag for LevelConfig {}
+impl Default for LevelConfig {
+    fn default() -> Self {
+        return Self {
+            dir: "levels".to_string(),
+            packs_dir: default_packs_dir(),
+            cur_level: "dev".to_string(),
+            gen_on_start: false,
+            save_on_start: false,
+        };
+    }
+}
+
+fn default_packs_dir() -> String {
+    return "packs".to_string();
+}
@@ -6,10 +6,24 @@ use super::ConfigTag;
 #[derive(Resource, Serialize, Deserialize)]
 pub struct MaterialConfig {
     pub player: HSL,
+    #[serde(default = "default_ghost")]
+    pub ghost: HSL,
 }
 impl ConfigTag for MaterialConfig {}
+impl Default for MaterialConfig {
+    fn default() -> Self {
+        return Self {
+            player: HSL::new(0.0, 0.8, 0.5),
+            ghost: default_ghost(),
+        };
+    }
+}
 
-#[derive(Resource, Serialize, Deserialize)]
+fn default_ghost() -> HSL {
+    return HSL::new(0.0, 0.4, 0.8);
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone)]
 pub struct HSL {
     pub h: f32,
     pub s: f32,
@@ -19,7 +33,10 @@ impl HSL {
     pub fn new(h: f32, s: f32, l: f32) -> Self {
         return Self { h, s, l };
     }
+    pub fn to_color(&self) -> Color {
+        return Color::hsl(self.h, self.s, self.l);
+    }
     pub fn to_color_mat(&self) -> ColorMaterial {
-        return ColorMaterial::from(Color::hsl(self.h, self.s, self.l));
+        return ColorMaterial::from(self.to_color());
     }
 }
@@ -8,3 +8,8 @@ pub struct MeshConfig {
     pub player: f32,
 }
 impl ConfigTag for MeshConfig {}
+impl Default for MeshConfig {
+    fn default() -> Self {
+        return Self { player: 5.0 };
+    }
+}
@@ -1,9 +1,13 @@
-use bevy::{app::Plugin, log::info};
+use bevy::{
+    app::Plugin,
+    log::{error, info, warn},
+};
 use level::LevelConfig;
 use player::PlayerConfig;
 use std::{
+    fmt::Display,
     fs::{self, File},
-    io::{BufReader, BufWriter},
+    io::{self, BufReader, BufWriter},
     path::Path,
 };
 
@@ -26,54 +30,144 @@ pub mod player;
 pub struct ConfigPlugin;
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(KeyBinds::load_cfg(KEY_BINDINGS_CFG_PATH))
-            .insert_resource(LevelConfig::load_cfg(LEVEL_CFG_PATH))
-            .insert_resource(MaterialConfig::load_cfg(MATERIAL_CFG_PATH))
-            .insert_resource(MeshConfig::load_cfg(MESH_CFG_PATH))
-            .insert_resource(PlayerConfig::load_cfg(PLAYER_CFG_PATH));
+        app.insert_resource(KeyBinds::load_or_default(KEY_BINDINGS_CFG_PATH))
+            .insert_resource(LevelConfig::load_or_default(LEVEL_CFG_PATH))
+            .insert_resource(MaterialConfig::load_or_default(MATERIAL_CFG_PATH))
+            .insert_resource(MeshConfig::load_or_default(MESH_CFG_PATH))
+            .insert_resource(PlayerConfig::load_or_default(PLAYER_CFG_PATH));
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: String,
+        err: io::Error,
+    },
+    Json {
+        path: String,
+        line: usize,
+        column: usize,
+        err: serde_json::Error,
+    },
+    UnsupportedVersion {
+        path: String,
+        version: u64,
+    },
+    NotAnObject {
+        path: String,
+    },
+    DuplicateKeyBind {
+        path: String,
+        first: String,
+        second: String,
+        key: String,
+    },
+}
+impl ConfigError {
+    pub fn io(path: &str, err: io::Error) -> Self {
+        return Self::Io {
+            path: path.to_string(),
+            err,
+        };
+    }
+
+    pub fn json(path: &str, err: serde_json::Error) -> Self {
+        return Self::Json {
+            path: path.to_string(),
+            line: err.line(),
+            column: err.column(),
+            err,
+        };
+    }
+
+    pub fn is_missing(&self) -> bool {
+        return match self {
+            ConfigError::Io { err, .. } => err.kind() == io::ErrorKind::NotFound,
+            _ => false,
+        };
+    }
+}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            ConfigError::Io { path, err } => write!(f, "Error accessing {path}, {err}"),
+            ConfigError::Json {
+                path,
+                line,
+                column,
+                err,
+            } => write!(f, "Error reading JSON at {path}:{line}:{column}, {err}"),
+            ConfigError::UnsupportedVersion { path, version } => {
+                write!(f, "Unsupported format version {version} in {path}")
+            }
+            ConfigError::NotAnObject { path } => write!(f, "Expected a JSON object in {path}"),
+            ConfigError::DuplicateKeyBind {
+                path,
+                first,
+                second,
+                key,
+            } => write!(f, "{first} and {second} are both bound to {key} in {path}"),
+        };
     }
 }
 
 pub trait ConfigTag {}
-pub trait Config {
-    fn load_cfg(path: &str) -> Self;
-    fn save_cfg(&self, path: &str);
+pub trait Config: Sized {
+    fn load_cfg(path: &str) -> Result<Self, ConfigError>;
+    fn save_cfg(&self, path: &str) -> Result<(), ConfigError>;
+
+    // A missing file is replaced with the defaults, a broken one is left alone so edits aren't lost
+    fn load_or_default(path: &str) -> Self
+    where
+        Self: Default,
+    {
+        let err = match Self::load_cfg(path) {
+            Ok(cfg) => return cfg,
+            Err(err) => err,
+        };
+        warn!("{err}, using defaults");
+
+        let cfg = Self::default();
+        if err.is_missing() {
+            if let Err(err) = cfg.save_cfg(path) {
+                error!("{err}");
+            }
+        }
+        return cfg;
+    }
 }
 
 impl<T> Config for T
 where
     T: ConfigTag + Serialize + for<'de> Deserialize<'de>,
 {
-    fn load_cfg(path: &str) -> T {
-        let file = File::open(path);
+    fn load_cfg(path: &str) -> Result<T, ConfigError> {
         info!("Loading config file at {path}");
-        if let Err(err) = file {
-            panic!("Error opening file {err:?}");
-        }
-
-        let reader = BufReader::new(file.unwrap());
+        let file = File::open(path).map_err(|err| ConfigError::io(path, err))?;
+        let reader = BufReader::new(file);
 
-        let json: Result<T, serde_json::Error> = serde_json::from_reader(reader);
-        match json {
-            Ok(cfg) => return cfg,
-            Err(err) => panic!("Error reading JSON from path {path}, {err:?}"),
-        }
+        return serde_json::from_reader(reader).map_err(|err| ConfigError::json(path, err));
     }
 
-    fn save_cfg(&self, path_str: &str) {
+    fn save_cfg(&self, path_str: &str) -> Result<(), ConfigError> {
         info!("Saving config file at {path_str}");
-        let path = Path::new(path_str);
-        if !path.exists() {
-            File::create(path).unwrap();
-        }
-
-        let writer = match fs::OpenOptions::new().truncate(true).write(true).open(path) {
-            Ok(file) => BufWriter::new(file),
-            Err(err) => panic!("Error Opening file at path {path_str}, {err:?}"),
-        };
+        return write_json(path_str, self);
+    }
+}
 
-        if let Err(err) = serde_json::to_writer_pretty(writer, self) {
-            panic!("Error writing JSON to path {path_str}, {err:?}")
-        }
+pub fn write_json<T: Serialize>(path_str: &str, value: &T) -> Result<(), ConfigError> {
+    let path = Path::new(path_str);
+    if !path.exists() {
+        File::create(path).map_err(|err| ConfigError::io(path_str, err))?;
     }
+
+    let file = fs::OpenOptions::new()
+        .truncate(true)
+        .write(true)
+        .open(path)
+        .map_err(|err| ConfigError::io(path_str, err))?;
+
+    return serde_json::to_writer_pretty(BufWriter::new(file), value)
+        .map_err(|err| ConfigError::json(path_str, err));
 }
@@ -12,3 +12,14 @@ pub struct PlayerConfig {
     pub angular_damping: f32,
 }
 impl ConfigTag for PlayerConfig {}
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        return Self {
+            collider_radius: 5.0,
+            cam_scale: 1.0,
+            move_speed: 10.0,
+            linear_damping: 10.0,
+            angular_damping: 1.0,
+        };
+    }
+}
@@ -1,4 +1,4 @@
-use bevy::{color::Color, math::Vec3};
+use bevy::color::Color;
 
 pub const KEY_BINDINGS_CFG_PATH: &str = "cfg/key_bindings.json";
 pub const DISPLAY_CFG_PATH: &str = "cfg/display.json";
@@ -6,6 +6,21 @@ pub const LEVEL_CFG_PATH: &str = "cfg/level.json";
 pub const MATERIAL_CFG_PATH: &str = "cfg/material.json";
 pub const MESH_CFG_PATH: &str = "cfg/mesh.json";
 pub const PLAYER_CFG_PATH: &str = "cfg/player.json";
+pub const HOT_RELOAD_SECS: f32 = 0.5;
+pub const SAVE_DIR_NAME: &str = "time_trial";
+pub const PROGRESS_FILE: &str = "progress.json";
+pub const SOLUTION_FILE: &str = "solution.json";
+pub const HEADLESS_GRACE_TICKS: usize = 60;
+pub const SOLVER_MAX_PHASES: usize = 8;
+pub const SOLVER_MAX_STATES: usize = 20_000;
+pub const SOLVER_MAX_WEIGHTS: usize = 64;
+pub const REPLAY_DIR_NAME: &str = "replays";
+pub const FIXED_TIMESTEP_HZ: f64 = 60.0;
+pub const PIXELS_PER_METER: f32 = 100.0;
+pub const EDITOR_GRID: f32 = 5.0;
+pub const EDITOR_ROTATE_STEP: f32 = std::f32::consts::PI / 12.0;
+pub const EDITOR_PAN_SPEED: f32 = 400.0;
+pub const EDITOR_ZOOM_STEP: f32 = 0.1;
 
 pub const PLAYER_Z_OFFSET: f32 = 1.0;
 pub const CAMERA_Z_OFFSET: f32 = 1.0;
@@ -17,6 +32,18 @@ pub const PROP_Z_OFFSET: f32 = 0.1;
 pub const INPUT_Z_OFFSET: f32 = 0.0;
 pub const OUTPUT_Z_OFFSET: f32 = 0.0;
 
+// How far a replayed entity may stray from its recording before the timeline breaks
+pub const PARADOX_TOLERANCE: f32 = 10.0;
+
+pub const PAST_OUTLINE_COLOR: Color = Color::hsla(0.0, 0.0, 0.0, 0.25);
+pub const SHIFT_FLASH_COLOR: Color = Color::hsla(0.0, 0.0, 1.0, 0.6);
+pub const SHIFT_FLASH_SECS: f32 = 0.3;
+
+pub const ERROR_DISPLAY_SECS: f32 = 5.0;
+pub const ERROR_COLOR: Color = Color::hsla(0.0, 0.8, 0.4, 1.0);
+
+pub const PACK_MANIFEST_FILE: &str = "pack.json";
+pub const LEVEL_FILE: &str = "level.json";
 pub const WALLS_FILE: &str = "walls.json";
 pub const PLAYER_FILE: &str = "player.json";
 pub const PROPS_FILE: &str = "props.json";
@@ -26,12 +53,8 @@ pub const OUTPUTS_FILE: &str = "outputs.json";
 pub const LOGIC_GRAPH_FILE: &str = "logic_graph.json";
 pub const GOAL_FILE: &str = "goal.json";
 pub const MATERIALS_FILE: &str = "materials.json";
+pub const TIME_FILE: &str = "time.json";
 
-pub const TEXT_SCALE: Vec3 = Vec3 {
-    x: 0.05,
-    y: 0.05,
-    z: 1.0,
-};
 pub const TEXT_COLOR: Color = Color::hsla(0.0, 0.0, 0.0, 1.0);
 pub const TEXT_SIZE: f32 = 16.0;
 
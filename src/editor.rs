@@ -0,0 +1,647 @@
+use bevy::{
+    app::{Plugin, Startup, Update},
+    asset::Assets,
+    input::{mouse::MouseWheel, ButtonInput},
+    log::{error, info},
+    math::Vec2,
+    prelude::{
+        default, in_state, state_changed, Bundle, Camera, Commands, Component, DetectChanges,
+        EventReader, EventWriter, GlobalTransform, IntoSystemConfigs, KeyCode, MouseButton,
+        NextState, OnEnter, OnExit, OrthographicProjection, Quat, Query, Res, ResMut, Resource,
+        State, TextBundle, Transform, Visibility, With,
+    },
+    sprite::ColorMaterial,
+    text::{Text, TextStyle},
+    time::Time,
+    ui::{PositionType, Style, Val},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    configuration::{key_bindings::KeyBinds, player::PlayerConfig, Config},
+    consts::{
+        EDITOR_GRID, EDITOR_PAN_SPEED, EDITOR_ROTATE_STEP, EDITOR_ZOOM_STEP, TEXT_COLOR, TEXT_SIZE,
+    },
+    handles::Handles,
+    level::{
+        blueprints::{
+            level::{BlueprintKind, BlueprintRef, LevelBlueprint, LevelMaterialHandles},
+            Placement,
+        },
+        level_pack::{ChangeLevelEvent, LevelError, LevelPack},
+        manager::LevelManager,
+    },
+    player::{player_bundle::Player, player_camera::PlayerCam},
+    ui::{error_display::ErrorEvent, ui_manager::GameState},
+};
+
+pub struct EditorPlugin;
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Editor>()
+            .add_systems(Startup, spawn_editor_hud)
+            .add_systems(OnEnter(GameState::Editor), start_editing)
+            .add_systems(OnExit(GameState::Editor), stop_editing)
+            .add_systems(
+                Update,
+                (
+                    toggle_editor,
+                    update_editor_hud_visibility.run_if(state_changed::<GameState>),
+                    (
+                        move_editor_camera,
+                        pick_tool,
+                        edit_with_mouse,
+                        edit_selected,
+                        save_level,
+                        respawn_edited_level,
+                        update_editor_hud,
+                    )
+                        .chain()
+                        .run_if(in_state(GameState::Editor)),
+                ),
+            );
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum EditorTool {
+    #[default]
+    Select,
+    Wall,
+    Box,
+    Button,
+    Door,
+    Goal,
+    PlayerStart,
+}
+impl EditorTool {
+    fn next(self) -> Self {
+        return match self {
+            EditorTool::Select => EditorTool::Wall,
+            EditorTool::Wall => EditorTool::Box,
+            EditorTool::Box => EditorTool::Button,
+            EditorTool::Button => EditorTool::Door,
+            EditorTool::Door => EditorTool::Goal,
+            EditorTool::Goal => EditorTool::PlayerStart,
+            EditorTool::PlayerStart => EditorTool::Select,
+        };
+    }
+
+    fn name(self) -> &'static str {
+        return match self {
+            EditorTool::Select => "Select",
+            EditorTool::Wall => "Place wall",
+            EditorTool::Box => "Place box",
+            EditorTool::Button => "Place button",
+            EditorTool::Door => "Place door",
+            EditorTool::Goal => "Move goal",
+            EditorTool::PlayerStart => "Set player start",
+        };
+    }
+
+    // The blueprint a click places, None for the tools that don't add anything
+    fn kind(self) -> Option<BlueprintKind> {
+        return match self {
+            EditorTool::Wall => Some(BlueprintKind::Wall),
+            EditorTool::Box => Some(BlueprintKind::Prop),
+            EditorTool::Button => Some(BlueprintKind::Input),
+            EditorTool::Door => Some(BlueprintKind::Output),
+            EditorTool::Goal => Some(BlueprintKind::Goal),
+            EditorTool::Select | EditorTool::PlayerStart => None,
+        };
+    }
+}
+
+// Edits are made to a copy of the current level's blueprint and only reach the level dir once
+// saved, leaving the editor reloads the level from disk
+#[derive(Resource, Default)]
+pub struct Editor {
+    blueprint: Option<LevelBlueprint>,
+    tool: EditorTool,
+    selected: Option<BlueprintRef>,
+    selected_materials: Vec<String>,
+    // Offset from the cursor to the selection's position while it is dragged
+    drag: Option<Vec2>,
+    respawn: bool,
+    unsaved: bool,
+    exit_warned: bool,
+}
+impl Editor {
+    fn select(&mut self, selected: Option<BlueprintRef>) {
+        self.selected = selected;
+        self.selected_materials = match (&mut self.blueprint, selected) {
+            (Some(blueprint), Some(blueprint_ref)) => blueprint
+                .material_keys_mut(blueprint_ref)
+                .into_iter()
+                .map(|key| key.clone())
+                .collect(),
+            _ => vec![],
+        };
+    }
+
+    // Structural changes respawn the level, moving things only updates their transforms
+    fn changed(&mut self, respawn: bool) {
+        self.respawn |= respawn;
+        self.unsaved = true;
+        self.exit_warned = false;
+    }
+}
+
+#[derive(Component)]
+pub struct EditorHud;
+
+#[derive(Bundle)]
+pub struct EditorHudBundle {
+    editor_hud: EditorHud,
+    text_bundle: TextBundle,
+}
+impl EditorHudBundle {
+    pub fn new(font_size: f32) -> Self {
+        return Self {
+            editor_hud: EditorHud,
+            text_bundle: TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        };
+    }
+}
+
+fn spawn_editor_hud(mut commands: Commands) {
+    commands.spawn(EditorHudBundle::new(TEXT_SIZE));
+}
+
+// Leaving with unsaved edits only warns, pressing the bind again without editing discards them
+fn toggle_editor(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut editor: ResMut<Editor>,
+) {
+    if !keys.just_pressed(key_binds.editor.0) {
+        return;
+    }
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Editor),
+        GameState::Editor if editor.unsaved && !editor.exit_warned => {
+            editor.exit_warned = true;
+            error_ev.send(ErrorEvent::new(&format!(
+                "Unsaved changes, save with {:?} or press {:?} again to discard them",
+                key_binds.editor_save.0, key_binds.editor.0
+            )));
+        }
+        GameState::Editor => next_state.set(GameState::InGame),
+        _ => {}
+    }
+}
+
+// Reloads the level in place so everything is back where its blueprint puts it without starting
+// a new attempt
+fn start_editing(
+    mut editor: ResMut<Editor>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+    level_pack: Res<LevelPack>,
+) {
+    *editor = Editor::default();
+    let loaded = match level_pack.cur_level_dir() {
+        Some(dir) => LevelBlueprint::load_cfg(&dir).map_err(LevelError::from),
+        None => Err(LevelError::EmptyPack(level_pack.dir().to_string())),
+    };
+    match loaded {
+        Ok(blueprint) => {
+            editor.blueprint = Some(blueprint);
+            change_level_ev.send(ChangeLevelEvent::reload_in_place());
+        }
+        Err(err) => {
+            let msg = format!("Unable to edit level, {err}");
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+            next_state.set(GameState::InGame);
+        }
+    }
+}
+
+// toggle_editor has already warned about any unsaved edits dropped here
+fn stop_editing(
+    mut editor: ResMut<Editor>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut cams: Query<(&mut Transform, &mut OrthographicProjection), With<PlayerCam>>,
+    player_config: Res<PlayerConfig>,
+) {
+    *editor = Editor::default();
+    change_level_ev.send(ChangeLevelEvent::reload_in_place());
+    for (mut transform, mut projection) in cams.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        projection.scale = player_config.cam_scale;
+    }
+}
+
+// The movement keys pan the camera away from the player and the scroll wheel zooms
+fn move_editor_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    time: Res<Time>,
+    mut mouse_wheel_ev: EventReader<MouseWheel>,
+    mut cams: Query<(&mut Transform, &mut OrthographicProjection), With<PlayerCam>>,
+) {
+    let mut dir = Vec2::ZERO;
+    if keys.pressed(key_binds.up.0) {
+        dir.y += 1.0;
+    }
+    if keys.pressed(key_binds.down.0) {
+        dir.y -= 1.0;
+    }
+    if keys.pressed(key_binds.left.0) {
+        dir.x -= 1.0;
+    }
+    if keys.pressed(key_binds.right.0) {
+        dir.x += 1.0;
+    }
+    let zoom = mouse_wheel_ev.read().map(|ev| ev.y.signum()).sum::<f32>();
+
+    for (mut transform, mut projection) in cams.iter_mut() {
+        let pan = dir * EDITOR_PAN_SPEED * projection.scale * time.delta_seconds();
+        transform.translation += pan.extend(0.0);
+        projection.scale = (projection.scale * (1.0 - zoom * EDITOR_ZOOM_STEP)).clamp(0.1, 10.0);
+    }
+}
+
+fn pick_tool(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    mut editor: ResMut<Editor>,
+) {
+    if keys.just_pressed(key_binds.editor_tool.0) {
+        editor.tool = editor.tool.next();
+        editor.drag = None;
+    }
+}
+
+fn edit_with_mouse(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cams: Query<(&Camera, &GlobalTransform), With<PlayerCam>>,
+    targets: Query<(&BlueprintRef, &GlobalTransform)>,
+    mut transforms: Query<(&BlueprintRef, &mut Transform)>,
+    mut editor: ResMut<Editor>,
+) {
+    let editor = &mut *editor;
+    let cursor = match cursor_world_pos(&windows, &cams) {
+        Some(cursor) => cursor,
+        None => return,
+    };
+    if buttons.just_released(MouseButton::Left) {
+        editor.drag = None;
+    }
+
+    if buttons.just_pressed(MouseButton::Left) {
+        let tool = editor.tool;
+        let blueprint = match &mut editor.blueprint {
+            Some(blueprint) => blueprint,
+            None => return,
+        };
+        match (tool, tool.kind()) {
+            (EditorTool::Select, _) => {
+                let selected = pick(cursor, &targets);
+                editor.drag = selected
+                    .and_then(|blueprint_ref| blueprint.placement(blueprint_ref))
+                    .map(|placement| placement.pos - cursor);
+                editor.select(selected);
+            }
+            (EditorTool::PlayerStart, _) => {
+                blueprint.set_player_start(snap(cursor));
+                editor.changed(true);
+            }
+            (_, Some(kind)) => {
+                let added = blueprint.add(kind, snap(cursor));
+                editor.select(Some(added));
+                editor.changed(true);
+            }
+            (_, None) => {}
+        }
+        return;
+    }
+
+    let (offset, selected) = match (editor.drag, editor.selected) {
+        (Some(offset), Some(selected)) if buttons.pressed(MouseButton::Left) => (offset, selected),
+        _ => return,
+    };
+    let moved = editor.blueprint.as_mut().and_then(|blueprint| {
+        let mut placement = blueprint.placement(selected)?;
+        let pos = snap(cursor + offset);
+        if pos == placement.pos {
+            return None;
+        }
+        placement.pos = pos;
+        blueprint.set_placement(selected, placement);
+        Some(placement)
+    });
+    if let Some(placement) = moved {
+        apply_placement(selected, placement, &mut transforms);
+        editor.changed(false);
+    }
+}
+
+// Rotates, resizes, recolours and removes the selection
+fn edit_selected(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    mut transforms: Query<(&BlueprintRef, &mut Transform)>,
+    mut editor: ResMut<Editor>,
+) {
+    let editor = &mut *editor;
+    let selected = match editor.selected {
+        Some(selected) => selected,
+        None => return,
+    };
+    if keys.just_pressed(key_binds.editor_deselect.0) {
+        editor.select(None);
+        return;
+    }
+    let blueprint = match &mut editor.blueprint {
+        Some(blueprint) => blueprint,
+        None => return,
+    };
+
+    if keys.just_pressed(key_binds.editor_delete.0) {
+        if blueprint.remove(selected) {
+            editor.select(None);
+            editor.changed(true);
+        }
+        return;
+    }
+
+    let material_slot = match (
+        keys.just_pressed(key_binds.editor_material.0),
+        keys.just_pressed(key_binds.editor_second_material.0),
+    ) {
+        (true, _) => Some(0),
+        (_, true) => Some(1),
+        _ => None,
+    };
+    if let Some(slot) = material_slot {
+        let names = blueprint.material_names();
+        if names.is_empty() {
+            return;
+        }
+        if let Some(key) = blueprint.material_keys_mut(selected).into_iter().nth(slot) {
+            let next = names
+                .iter()
+                .position(|name| name == key)
+                .map_or(0, |i| (i + 1) % names.len());
+            *key = names[next].clone();
+            editor.select(Some(selected));
+            editor.changed(true);
+        }
+        return;
+    }
+
+    let mut rotate = 0.0;
+    if keys.just_pressed(key_binds.editor_rotate_left.0) {
+        rotate += EDITOR_ROTATE_STEP;
+    }
+    if keys.just_pressed(key_binds.editor_rotate_right.0) {
+        rotate -= EDITOR_ROTATE_STEP;
+    }
+    let mut resize = Vec2::ZERO;
+    if keys.just_pressed(key_binds.editor_grow_width.0) {
+        resize.x += EDITOR_GRID;
+    }
+    if keys.just_pressed(key_binds.editor_shrink_width.0) {
+        resize.x -= EDITOR_GRID;
+    }
+    if keys.just_pressed(key_binds.editor_grow_height.0) {
+        resize.y += EDITOR_GRID;
+    }
+    if keys.just_pressed(key_binds.editor_shrink_height.0) {
+        resize.y -= EDITOR_GRID;
+    }
+    if rotate == 0.0 && resize == Vec2::ZERO {
+        return;
+    }
+
+    // Buttons are round so they only have a radius and no rotation
+    if selected.kind == BlueprintKind::Input {
+        rotate = 0.0;
+        resize = Vec2::splat(resize.x + resize.y);
+    }
+    if let Some(mut placement) = blueprint.placement(selected) {
+        placement.z_rot += rotate;
+        placement.shape = (placement.shape + resize).max(Vec2::splat(EDITOR_GRID));
+        blueprint.set_placement(selected, placement);
+        apply_placement(selected, placement, &mut transforms);
+        editor.changed(false);
+    }
+}
+
+// Saving an unfinished logic graph is allowed, the problems are shown so they can be fixed by hand
+fn save_level(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    level_pack: Res<LevelPack>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut editor: ResMut<Editor>,
+) {
+    if !keys.just_pressed(key_binds.editor_save.0) {
+        return;
+    }
+    let blueprint = match &editor.blueprint {
+        Some(blueprint) => blueprint,
+        None => return,
+    };
+
+    let dir = match level_pack.cur_level_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+    let msg = match blueprint.save_cfg(&dir) {
+        Ok(()) => match blueprint.validate() {
+            Ok(()) => format!("Saved {dir}"),
+            Err(err) => format!("Saved {dir}, but {err}"),
+        },
+        Err(err) => {
+            let msg = format!("Unable to save level, {err}");
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+            return;
+        }
+    };
+    info!("{msg}");
+    error_ev.send(ErrorEvent::new(&msg));
+    editor.unsaved = false;
+}
+
+fn respawn_edited_level(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    mut level_manager: ResMut<LevelManager>,
+    mut player: Query<&mut Transform, With<Player>>,
+    mut level_material_handles: ResMut<LevelMaterialHandles>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    handles: Res<Handles>,
+) {
+    if !editor.respawn {
+        return;
+    }
+    editor.respawn = false;
+
+    if let (Some(blueprint), Ok(mut transform)) = (&editor.blueprint, player.get_single_mut()) {
+        level_manager.change_level(
+            blueprint.clone(),
+            &mut transform.translation,
+            &mut commands,
+            &handles,
+            &mut level_material_handles,
+            &mut materials,
+        );
+    }
+}
+
+fn update_editor_hud(
+    editor: Res<Editor>,
+    key_binds: Res<KeyBinds>,
+    mut huds: Query<&mut Text, With<EditorHud>>,
+) {
+    if !editor.is_changed() && !key_binds.is_changed() {
+        return;
+    }
+    let blueprint = match &editor.blueprint {
+        Some(blueprint) => blueprint,
+        None => return,
+    };
+
+    let mut lines = vec![
+        format!(
+            "Level editor{}",
+            match editor.unsaved {
+                true => " (unsaved)",
+                false => "",
+            }
+        ),
+        format!("Tool: {}", editor.tool.name()),
+        format!(
+            "Player start: ({}, {})",
+            blueprint.player_start().x,
+            blueprint.player_start().y
+        ),
+    ];
+    match editor.selected {
+        Some(selected) => {
+            if let Some(placement) = blueprint.placement(selected) {
+                lines.push(format!(
+                    "Selected: {:?} {} at ({}, {}), size ({}, {}), rotation {:.0}",
+                    selected.kind,
+                    selected.index,
+                    placement.pos.x,
+                    placement.pos.y,
+                    placement.shape.x,
+                    placement.shape.y,
+                    placement.z_rot.to_degrees()
+                ));
+            }
+            lines.push(format!(
+                "Materials: {}",
+                editor.selected_materials.join(", ")
+            ));
+        }
+        None => lines.push("Selected: none".to_string()),
+    }
+    lines.push(format!(
+        "{:?} tool, click place or select, drag move, {:?} deselect, {:?}/{:?} rotate, \
+        {:?}/{:?}/{:?}/{:?} resize, {:?}/{:?} material, {:?} remove, {:?} save",
+        key_binds.editor_tool.0,
+        key_binds.editor_deselect.0,
+        key_binds.editor_rotate_left.0,
+        key_binds.editor_rotate_right.0,
+        key_binds.editor_grow_width.0,
+        key_binds.editor_shrink_width.0,
+        key_binds.editor_grow_height.0,
+        key_binds.editor_shrink_height.0,
+        key_binds.editor_material.0,
+        key_binds.editor_second_material.0,
+        key_binds.editor_delete.0,
+        key_binds.editor_save.0
+    ));
+
+    for mut text in huds.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn update_editor_hud_visibility(
+    state: Res<State<GameState>>,
+    mut huds: Query<&mut Visibility, With<EditorHud>>,
+) {
+    for mut visibility in huds.iter_mut() {
+        *visibility = match state.get() {
+            GameState::Editor => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn cursor_world_pos(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cams: &Query<(&Camera, &GlobalTransform), With<PlayerCam>>,
+) -> Option<Vec2> {
+    let cursor = windows.get_single().ok()?.cursor_position()?;
+    let (camera, cam_transform) = cams.get_single().ok()?;
+    return camera.viewport_to_world_2d(cam_transform, cursor);
+}
+
+fn snap(pos: Vec2) -> Vec2 {
+    return (pos / EDITOR_GRID).round() * EDITOR_GRID;
+}
+
+// The topmost level entity under the cursor, meshes are unit sized so the test is done in their
+// local space
+fn pick(cursor: Vec2, targets: &Query<(&BlueprintRef, &GlobalTransform)>) -> Option<BlueprintRef> {
+    return targets
+        .iter()
+        .filter(|(blueprint_ref, transform)| {
+            let local = transform
+                .affine()
+                .inverse()
+                .transform_point3(cursor.extend(transform.translation().z));
+            match blueprint_ref.kind {
+                BlueprintKind::Input => local.truncate().length() <= 0.5,
+                _ => local.x.abs() <= 0.5 && local.y.abs() <= 0.5,
+            }
+        })
+        .max_by(|(_, a), (_, b)| a.translation().z.total_cmp(&b.translation().z))
+        .map(|(blueprint_ref, _)| *blueprint_ref);
+}
+
+fn apply_placement(
+    blueprint_ref: BlueprintRef,
+    placement: Placement,
+    transforms: &mut Query<(&BlueprintRef, &mut Transform)>,
+) {
+    for (other, mut transform) in transforms.iter_mut() {
+        if *other == blueprint_ref {
+            transform.translation.x = placement.pos.x;
+            transform.translation.y = placement.pos.y;
+            transform.rotation = Quat::from_rotation_z(placement.z_rot);
+            transform.scale = placement.shape.extend(1.0);
+        }
+    }
+}
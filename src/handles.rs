@@ -1,7 +1,7 @@
 use bevy::{
-    app::{Plugin, PreStartup},
+    app::{Plugin, PreStartup, Update},
     ecs::system::Res,
-    prelude::{Assets, Circle, Commands, Handle, Mesh, Rectangle, ResMut, Resource},
+    prelude::{Assets, Circle, Commands, DetectChanges, Handle, Mesh, Rectangle, ResMut, Resource},
     sprite::{ColorMaterial, Mesh2dHandle},
 };
 
@@ -11,6 +11,7 @@ use crate::configuration::{material::MaterialConfig, mesh::MeshConfig};
 pub struct Handles {
     pub player_mesh: Mesh2dHandle,
     pub player_material: Handle<ColorMaterial>,
+    pub ghost_material: Handle<ColorMaterial>,
     pub square_mesh: Mesh2dHandle,
     pub circle_mesh: Mesh2dHandle,
 }
@@ -18,7 +19,8 @@ pub struct Handles {
 pub struct HandlesPlugin;
 impl Plugin for HandlesPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(PreStartup, setup_handles);
+        app.add_systems(PreStartup, setup_handles)
+            .add_systems(Update, update_material_handles);
     }
 }
 
@@ -32,7 +34,25 @@ pub fn setup_handles(
     commands.insert_resource(Handles {
         player_mesh: meshes.add(Circle::new(mesh_config.player)).into(),
         player_material: materials.add(materials_config.player.to_color_mat()),
+        ghost_material: materials.add(materials_config.ghost.to_color_mat()),
         square_mesh: meshes.add(Rectangle::new(1.0, 1.0)).into(),
         circle_mesh: meshes.add(Circle::new(0.5)).into(),
     });
 }
+
+// Picks up a reloaded MaterialConfig by recolouring the existing materials
+fn update_material_handles(
+    handles: Res<Handles>,
+    materials_config: Res<MaterialConfig>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !materials_config.is_changed() || materials_config.is_added() {
+        return;
+    }
+    if let Some(material) = materials.get_mut(&handles.player_material) {
+        material.color = materials_config.player.to_color();
+    }
+    if let Some(material) = materials.get_mut(&handles.ghost_material) {
+        material.color = materials_config.ghost.to_color();
+    }
+}
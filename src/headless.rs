@@ -0,0 +1,226 @@
+use std::{path::Path, time::Duration};
+
+use bevy::{
+    app::{App, FixedUpdate, Plugin},
+    asset::{AssetApp, AssetPlugin},
+    hierarchy::HierarchyPlugin,
+    input::InputPlugin,
+    prelude::{
+        AppExtStates, IntoSystemConfigs, Mesh, Query, Res, ResMut, Resource, TransformPlugin,
+    },
+    sprite::ColorMaterial,
+    state::app::StatesPlugin,
+    time::{Fixed, Time, TimeUpdateStrategy},
+    MinimalPlugins,
+};
+use bevy_rapier2d::prelude::{NoUserData, RapierPhysicsPlugin};
+
+use crate::{
+    configuration::{level::LevelConfig, Config, ConfigError, ConfigPlugin},
+    consts::{
+        FIXED_TIMESTEP_HZ, HEADLESS_GRACE_TICKS, LEVEL_CFG_PATH, PIXELS_PER_METER, SOLUTION_FILE,
+    },
+    handles::HandlesPlugin,
+    level::{
+        bundles::goal::Goal, goal::update_goal, level_pack::LevelPackRegistry,
+        manager::LevelManagerPlugin,
+    },
+    player::player_bundle::PlayerPlugin,
+    replay::{fixed_rapier_config, Replay, ReplayPlugin, ReplayState},
+    ui::{
+        error_display::ErrorEvent, events::UIEventPlugin, stopwatch_display::ResetStopwatchEvent,
+        ui_manager::GameState,
+    },
+};
+
+pub struct HeadlessPlugin;
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeadlessRun>()
+            .add_systems(FixedUpdate, check_goal.after(update_goal));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct HeadlessRun {
+    solved_tick: Option<u32>,
+}
+
+fn check_goal(goals: Query<&Goal>, replay_state: Res<ReplayState>, mut run: ResMut<HeadlessRun>) {
+    if run.solved_tick.is_none() && goals.iter().any(|goal| goal.triggered) {
+        run.solved_tick = Some(replay_state.tick());
+    }
+}
+
+// No window or renderer, each update advances exactly one fixed tick
+pub fn headless_app(replay_path: &str) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        HierarchyPlugin,
+        InputPlugin,
+        StatesPlugin,
+        TransformPlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / FIXED_TIMESTEP_HZ,
+    )))
+    .insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
+    .insert_resource(fixed_rapier_config())
+    .insert_state(GameState::InGame)
+    .add_event::<ErrorEvent>()
+    .add_event::<ResetStopwatchEvent>()
+    .add_plugins((
+        ConfigPlugin,
+        HandlesPlugin,
+        HeadlessPlugin,
+        LevelManagerPlugin,
+        PlayerPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER).in_fixed_schedule(),
+        ReplayPlugin::new(Some(replay_path.to_string())),
+        UIEventPlugin,
+    ));
+    app.finish();
+    app.cleanup();
+    return app;
+}
+
+// Returns the tick the goal was reached on, None if the inputs ran out first
+fn run_replay(replay_path: &str) -> Result<Option<u32>, ConfigError> {
+    let replay = Replay::load_cfg(replay_path)?;
+    let mut app = headless_app(replay_path);
+
+    // The first update starts the level, the grace ticks let the player coast into the goal
+    for _ in 0..=replay.inputs.len() + HEADLESS_GRACE_TICKS {
+        app.update();
+        if let Some(tick) = app.world().resource::<HeadlessRun>().solved_tick {
+            return Ok(Some(tick));
+        }
+    }
+    return Ok(None);
+}
+
+// Without replay paths the solution replay of every level in every pack is checked, false if any
+// level has no solution since it would otherwise pass unchecked
+fn solution_paths() -> (Vec<String>, bool) {
+    let level_config = LevelConfig::load_or_default(LEVEL_CFG_PATH);
+    let registry = LevelPackRegistry::scan(&level_config.dir, &level_config.packs_dir);
+
+    let mut paths = vec![];
+    let mut all_found = true;
+    for pack in registry.packs() {
+        for level in pack.manifest.levels.iter() {
+            let path = format!("{}/{}/{}", pack.dir, level.dir, SOLUTION_FILE);
+            if Path::new(&path).exists() {
+                paths.push(path);
+            } else {
+                println!(
+                    "{}/{}: no {SOLUTION_FILE}, save a replay that reaches the goal there",
+                    pack.dir, level.dir
+                );
+                all_found = false;
+            }
+        }
+    }
+    return (paths, all_found);
+}
+
+// Prints one line per replay, true when every replay was found and reached the goal
+pub fn run_headless(replay_paths: &[String]) -> bool {
+    let (replay_paths, mut all_solved) = match replay_paths.is_empty() {
+        true => solution_paths(),
+        false => (replay_paths.to_vec(), true),
+    };
+
+    for path in replay_paths.iter() {
+        match run_replay(path) {
+            Ok(Some(tick)) => println!("{path}: solved on tick {tick}"),
+            Ok(None) => {
+                println!("{path}: not solved");
+                all_solved = false;
+            }
+            Err(err) => {
+                println!("{path}: {err}");
+                all_solved = false;
+            }
+        }
+    }
+    return all_solved;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use bevy::prelude::{Transform, With};
+    use bevy_trait_query::One;
+
+    use super::headless_app;
+    use crate::{
+        configuration::{level::LevelConfig, player::PlayerConfig, Config},
+        level::{input::Input, manager::LevelManagerPlugin},
+        player::player_bundle::Player,
+        replay::{PlayerInput, Replay},
+    };
+
+    #[test]
+    fn replay_is_deterministic() {
+        // The generated level in a pack of its own so the test doesn't depend on the working dir
+        let dir = env::temp_dir().join("time_trial_determinism");
+        let _ = fs::remove_dir_all(&dir);
+        let levels_dir = dir.join("levels");
+        fs::create_dir_all(&levels_dir).unwrap();
+        let levels_dir = levels_dir.to_string_lossy().to_string();
+        LevelManagerPlugin::gen_blueprint()
+            .save_cfg(&format!("{levels_dir}/00"))
+            .unwrap();
+
+        let mut replay = Replay::new(&levels_dir, "00", 0);
+        replay.content_hash = None;
+        replay.inputs = (0..240)
+            .map(|tick| {
+                PlayerInput {
+                    up: tick % 60 < 30,
+                    right: tick < 160,
+                    time_shift: tick == 120,
+                    ..Default::default()
+                }
+                .to_bits()
+            })
+            .collect();
+        let path = dir.join("replay.json").to_string_lossy().to_string();
+        replay.save_cfg(&path).unwrap();
+
+        // Player position and every input's state once the whole recording has played
+        let run = || {
+            let mut app = headless_app(&path);
+            app.insert_resource(LevelConfig {
+                dir: levels_dir.clone(),
+                packs_dir: dir.join("packs").to_string_lossy().to_string(),
+                ..Default::default()
+            })
+            .insert_resource(PlayerConfig::default());
+            for _ in 0..=replay.inputs.len() {
+                app.update();
+            }
+            let world = app.world_mut();
+            let mut players = world.query_filtered::<&Transform, With<Player>>();
+            let player_pos = players.single(world).translation;
+            let mut inputs = world.query::<One<&dyn Input>>();
+            let mut states = vec![];
+            for input in inputs.iter(world) {
+                input.append_state(&mut states);
+            }
+            return (player_pos, states);
+        };
+
+        let (first_pos, first_states) = run();
+        let (second_pos, second_states) = run();
+        assert!(first_pos == second_pos);
+        assert!(first_states == second_states);
+        assert!(!first_states.is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::SystemTime};
+
+use bevy::{
+    app::{Plugin, Update},
+    log::{error, info},
+    prelude::{in_state, Commands, EventWriter, IntoSystemConfigs, Res, ResMut, Resource},
+    time::{Time, Timer, TimerMode},
+};
+
+use crate::{
+    configuration::{
+        key_bindings::KeyBinds, material::MaterialConfig, player::PlayerConfig, Config,
+    },
+    consts::{HOT_RELOAD_SECS, KEY_BINDINGS_CFG_PATH, MATERIAL_CFG_PATH, PLAYER_CFG_PATH},
+    level::level_pack::{ChangeLevelEvent, LevelPack},
+    ui::{error_display::ErrorEvent, ui_manager::GameState},
+};
+
+pub struct HotReloadPlugin;
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.insert_resource(FileWatcher::new(HOT_RELOAD_SECS))
+            .add_systems(Update, watch_files.run_if(in_state(GameState::InGame)));
+    }
+}
+
+// Polls modification times, which is plenty for a handful of small JSON files
+#[derive(Resource)]
+pub struct FileWatcher {
+    timer: Timer,
+    modified: HashMap<PathBuf, SystemTime>,
+}
+impl FileWatcher {
+    pub fn new(secs: f32) -> Self {
+        return Self {
+            timer: Timer::from_seconds(secs, TimerMode::Repeating),
+            modified: HashMap::new(),
+        };
+    }
+
+    // Whether the file, or any file directly inside the dir, changed since the last poll. Files
+    // seen for the first time are only recorded
+    pub fn poll(&mut self, path: &str) -> bool {
+        let files = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>(),
+            Err(_) => vec![PathBuf::from(path)],
+        };
+
+        let mut changed = false;
+        for file in files {
+            if let Ok(modified) = fs::metadata(&file).and_then(|m| m.modified()) {
+                if let Some(prev) = self.modified.insert(file, modified) {
+                    changed |= prev != modified;
+                }
+            }
+        }
+        return changed;
+    }
+}
+
+fn watch_files(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut watcher: ResMut<FileWatcher>,
+    level_pack: Res<LevelPack>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if watcher.poll(KEY_BINDINGS_CFG_PATH) {
+        reload_cfg::<KeyBinds>(KEY_BINDINGS_CFG_PATH, &mut commands, &mut error_ev);
+    }
+    if watcher.poll(PLAYER_CFG_PATH) {
+        reload_cfg::<PlayerConfig>(PLAYER_CFG_PATH, &mut commands, &mut error_ev);
+    }
+    if watcher.poll(MATERIAL_CFG_PATH) {
+        reload_cfg::<MaterialConfig>(MATERIAL_CFG_PATH, &mut commands, &mut error_ev);
+    }
+
+    if let Some(dir) = level_pack.cur_level_dir() {
+        if watcher.poll(&dir) {
+            info!("Reloading level {dir}");
+            change_level_ev.send(ChangeLevelEvent::reload_in_place());
+        }
+    }
+}
+
+// A broken file keeps the current settings so a half finished edit doesn't undo anything
+fn reload_cfg<T: Config + Resource>(
+    path: &str,
+    commands: &mut Commands,
+    error_ev: &mut EventWriter<ErrorEvent>,
+) {
+    match T::load_cfg(path) {
+        Ok(cfg) => {
+            info!("Reloaded config file at {path}");
+            commands.insert_resource(cfg);
+        }
+        Err(err) => {
+            error!("{err}");
+            error_ev.send(ErrorEvent::new(&err.to_string()));
+        }
+    }
+}
@@ -8,9 +8,11 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{configuration::ConfigTag, level::bundles::goal::GoalBundle};
+use crate::level::bundles::goal::GoalBundle;
 
-#[derive(Deserialize, Serialize)]
+use super::Placement;
+
+#[derive(Deserialize, Serialize, Clone)]
 pub struct GoalBlueprint {
     pos: Vec2,
     z_rot: f32,
@@ -26,6 +28,20 @@ impl GoalBlueprint {
             material_key: material_key.to_string(),
         };
     }
+    pub fn placement(&self) -> Placement {
+        return Placement::new(self.pos, self.z_rot, self.shape);
+    }
+
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.pos = placement.pos;
+        self.z_rot = placement.z_rot;
+        self.shape = placement.shape;
+    }
+
+    pub fn material_keys_mut(&mut self) -> Vec<&mut String> {
+        return vec![&mut self.material_key];
+    }
+
     pub fn spawn(
         self,
         commands: &mut Commands,
@@ -42,4 +58,3 @@ impl GoalBlueprint {
         .spawn(commands);
     }
 }
-impl ConfigTag for GoalBlueprint {}
@@ -9,22 +9,35 @@ use bevy::{
 use serde::{Deserialize, Serialize};
 
 use crate::level::{
-    bundles::input::{PressButtonBundle, ToggleButtonBundle},
+    bundles::input::{DialBundle, PressButtonBundle, PressurePlateBundle, ToggleButtonBundle},
     input::ButtonType,
 };
 
-#[derive(Deserialize, Serialize)]
+use super::Placement;
+
+#[derive(Deserialize, Serialize, Clone)]
 pub enum InputBlueprint {
     Button(ButtonBlueprint),
 }
+impl InputBlueprint {
+    // One entry per state the spawned Input appends, naming the node it is wired to. None
+    // falls back to the next of the logic graph's input_nodes
+    pub fn slots(&self) -> Vec<Option<String>> {
+        return match self {
+            InputBlueprint::Button(button_blueprint) => vec![button_blueprint.node.clone()],
+        };
+    }
+}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct ButtonBlueprint {
     pos: Vec2,
     radius: f32,
     button_type: ButtonType,
     on_material_key: String,
     off_material_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node: Option<String>,
 }
 impl ButtonBlueprint {
     pub fn new(
@@ -40,9 +53,28 @@ impl ButtonBlueprint {
             button_type: button_type,
             on_material_key: on_material_key.to_string(),
             off_material_key: off_material_key.to_string(),
+            node: None,
         };
     }
 
+    // Buttons are round, the shape's width is used as the radius
+    pub fn placement(&self) -> Placement {
+        return Placement::new(self.pos, 0.0, Vec2::splat(self.radius));
+    }
+
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.pos = placement.pos;
+        self.radius = placement.shape.x;
+    }
+
+    pub fn material_keys_mut(&mut self) -> Vec<&mut String> {
+        return vec![&mut self.off_material_key, &mut self.on_material_key];
+    }
+
+    pub fn button_type(&self) -> &ButtonType {
+        return &self.button_type;
+    }
+
     pub fn spawn(
         self,
         commands: &mut Commands,
@@ -68,6 +100,25 @@ impl ButtonBlueprint {
                 &self.off_material_key,
             )
             .spawn(commands),
+            ButtonType::PressurePlate => PressurePlateBundle::new(
+                &materials[&self.off_material_key],
+                &mesh,
+                self.radius,
+                self.pos,
+                &self.on_material_key,
+                &self.off_material_key,
+            )
+            .spawn(commands),
+            ButtonType::Dial { steps } => DialBundle::new(
+                &materials[&self.off_material_key],
+                &mesh,
+                self.radius,
+                self.pos,
+                *steps,
+                &self.on_material_key,
+                &self.off_material_key,
+            )
+            .spawn(commands),
         };
     }
 }
@@ -2,29 +2,39 @@ use std::{collections::HashMap, fs::create_dir, path::Path};
 
 use bevy::{
     asset::{Assets, Handle},
-    log::error,
-    math::{Vec2, Vec3},
-    prelude::{BuildChildren, Commands, Resource},
+    color::Color,
+    math::{vec2, Vec2, Vec3},
+    prelude::{BuildChildren, Commands, Component, Resource},
     sprite::ColorMaterial,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    configuration::{material::HSL, Config, ConfigTag},
-    consts::{
-        GOAL_FILE, INPUTS_FILE, LOGIC_GRAPH_FILE, MATERIALS_FILE, OUTPUTS_FILE, PLAYER_FILE,
-        PLAYER_Z_OFFSET, PROPS_FILE, WALLS_FILE,
-    },
+    configuration::{material::HSL, write_json, Config, ConfigError},
+    consts::{LEVEL_FILE, PLAYER_Z_OFFSET, SOLVER_MAX_WEIGHTS},
     handles::Handles,
-    level::{bundles::level::LevelRootBundle, level::Level, logic_graph::LogicGraph},
+    level::{
+        bundles::level::LevelRootBundle,
+        input::ButtonType,
+        level::Level,
+        logic_graph::{LogicGraph, LogicGraphError},
+        logic_solver::SolverInput,
+    },
 };
 
 use super::{
-    goal::GoalBlueprint, input::InputBlueprint, output::OutputBluePrint, props::PropBlueprint,
+    goal::GoalBlueprint,
+    input::{ButtonBlueprint, InputBlueprint},
+    migrate::{migrate, read_json, read_split_level, LEVEL_FORMAT_VERSION},
+    output::{DoorBlueprint, OutputBluePrint},
+    props::{BoxBlueprint, PropBlueprint},
     wall::WallBluePrint,
+    Placement,
 };
 
+#[derive(Deserialize, Serialize, Clone)]
 pub struct LevelBlueprint {
+    format_version: u64,
     player: PlayerBlueprint,
     walls: WallBlueprints,
     props: PropBlueprints,
@@ -32,39 +42,29 @@ pub struct LevelBlueprint {
     outputs: OutputBlueprints,
     goal: GoalBlueprint,
     pub logic_graph: LogicGraph,
+    #[serde(rename = "materials")]
     level_materials: LevelMaterials,
+    time: TimeBlueprint,
 }
+// A level is a dir holding either a single LEVEL_FILE document or the older one file per section
+// layout, both are migrated up to LEVEL_FORMAT_VERSION and saved as a single document
 impl Config for LevelBlueprint {
-    fn load_cfg(path: &str) -> Self {
-        return Self {
-            player: PlayerBlueprint::load_cfg(&format!("{}/{}", path, PLAYER_FILE)),
-            walls: WallBlueprints::load_cfg(&format!("{}/{}", path, WALLS_FILE)),
-            props: PropBlueprints::load_cfg(&format!("{}/{}", path, PROPS_FILE)),
-            inputs: InputBlueprints::load_cfg(&format!("{}/{}", path, INPUTS_FILE)),
-            outputs: OutputBlueprints::load_cfg(&format!("{}/{}", path, OUTPUTS_FILE)),
-            goal: GoalBlueprint::load_cfg(&format!("{}/{}", path, GOAL_FILE)),
-            logic_graph: LogicGraph::load_cfg(&format!("{}/{}", path, LOGIC_GRAPH_FILE)),
-            level_materials: LevelMaterials::load_cfg(&format!("{}/{}", path, MATERIALS_FILE)),
+    fn load_cfg(path: &str) -> Result<Self, ConfigError> {
+        let level_file = format!("{}/{}", path, LEVEL_FILE);
+        let mut doc = match Path::new(&level_file).exists() {
+            true => read_json(&level_file)?,
+            false => read_split_level(path)?,
         };
+        migrate(path, &mut doc)?;
+
+        return serde_json::from_value(doc).map_err(|err| ConfigError::json(path, err));
     }
 
-    fn save_cfg(&self, path: &str) {
+    fn save_cfg(&self, path: &str) -> Result<(), ConfigError> {
         if !Path::new(path).exists() {
-            if let Err(err) = create_dir(path) {
-                error!("Error creating dir {path}, {err}");
-            }
+            create_dir(path).map_err(|err| ConfigError::io(path, err))?;
         }
-
-        self.player.save_cfg(&format!("{}/{}", path, PLAYER_FILE));
-        self.walls.save_cfg(&format!("{}/{}", path, WALLS_FILE));
-        self.props.save_cfg(&format!("{}/{}", path, PROPS_FILE));
-        self.inputs.save_cfg(&format!("{}/{}", path, INPUTS_FILE));
-        self.outputs.save_cfg(&format!("{}/{}", path, OUTPUTS_FILE));
-        self.goal.save_cfg(&format!("{}/{}", path, GOAL_FILE));
-        self.logic_graph
-            .save_cfg(&format!("{}/{}", path, LOGIC_GRAPH_FILE));
-        self.level_materials
-            .save_cfg(&format!("{}/{}", path, MATERIALS_FILE));
+        return write_json(&format!("{}/{}", path, LEVEL_FILE), self);
     }
 }
 impl LevelBlueprint {
@@ -77,8 +77,10 @@ impl LevelBlueprint {
         goal: GoalBlueprint,
         logic_graph: LogicGraph,
         level_materials: HashMap<String, HSL>,
+        max_loops: usize,
     ) -> Self {
         return Self {
+            format_version: LEVEL_FORMAT_VERSION,
             player: PlayerBlueprint(player),
             walls: WallBlueprints(walls),
             props: PropBlueprints(props),
@@ -86,8 +88,285 @@ impl LevelBlueprint {
             outputs: OutputBlueprints(outputs),
             goal: goal,
             logic_graph,
-            level_materials: LevelMaterials(level_materials),
+            level_materials: LevelMaterials {
+                present: level_materials,
+                past: HashMap::new(),
+            },
+            time: TimeBlueprint { max_loops },
+        };
+    }
+
+    pub fn max_loops(&self) -> usize {
+        return self.time.max_loops;
+    }
+
+    // FNV-1a over the serialized level, JSON objects are key sorted so map order doesn't matter
+    pub fn content_hash(&self) -> u64 {
+        let json = serde_json::to_value(self)
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        return json.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    }
+
+    fn input_slots(&self) -> Vec<Option<String>> {
+        return self.inputs.0.iter().flat_map(|i| i.slots()).collect();
+    }
+
+    fn output_slots(&self) -> Vec<Option<String>> {
+        return self.outputs.0.iter().flat_map(|o| o.slots()).collect();
+    }
+
+    pub fn validate(&self) -> Result<(), LogicGraphError> {
+        return self
+            .logic_graph
+            .validate(&self.input_slots(), &self.output_slots());
+    }
+
+    // The logic graph wired up the same way spawning the level would
+    pub fn compiled_logic_graph(&self) -> LogicGraph {
+        let mut logic_graph = self.logic_graph.clone();
+        logic_graph.compile(&self.input_slots(), &self.output_slots());
+        return logic_graph;
+    }
+
+    // Pressure plates can be weighed down by any group of the player and the props
+    pub fn solver_inputs(&self, player_weight: f32) -> Vec<SolverInput> {
+        // Past selves weigh as much as the player and can stand on plates too
+        let mut weights = vec![player_weight; 1 + self.max_loops()];
+        weights.extend(self.props.0.iter().map(|prop| match prop {
+            PropBlueprint::BoxBlueprint(box_blueprint) => box_blueprint.weight(),
+        }));
+
+        return self
+            .inputs
+            .0
+            .iter()
+            .map(|input| match input {
+                InputBlueprint::Button(button_blueprint) => match button_blueprint.button_type() {
+                    ButtonType::ToggleButton => SolverInput::button(false),
+                    ButtonType::PressButton => SolverInput::button(true),
+                    ButtonType::PressurePlate => {
+                        SolverInput::pressure_plate(&weights, SOLVER_MAX_WEIGHTS)
+                    }
+                    ButtonType::Dial { steps } => SolverInput::dial(*steps),
+                },
+            })
+            .collect();
+    }
+
+    // Held inputs can be kept down by the player, a past self per loop and every prop
+    pub fn solver_holders(&self) -> usize {
+        return 1 + self.max_loops() + self.props.0.len();
+    }
+
+    pub fn player_start(&self) -> Vec2 {
+        return self.player.0;
+    }
+
+    pub fn set_player_start(&mut self, pos: Vec2) {
+        self.player.0 = pos;
+    }
+
+    pub fn placement(&self, blueprint_ref: BlueprintRef) -> Option<Placement> {
+        let index = blueprint_ref.index;
+        return match blueprint_ref.kind {
+            BlueprintKind::Wall => self.walls.0.get(index).map(|wall| wall.placement()),
+            BlueprintKind::Prop => self.props.0.get(index).map(|prop| match prop {
+                PropBlueprint::BoxBlueprint(box_blueprint) => box_blueprint.placement(),
+            }),
+            BlueprintKind::Input => self.inputs.0.get(index).map(|input| match input {
+                InputBlueprint::Button(button_blueprint) => button_blueprint.placement(),
+            }),
+            BlueprintKind::Output => self.outputs.0.get(index).map(|output| match output {
+                OutputBluePrint::Door(door_blueprint) => door_blueprint.placement(),
+            }),
+            BlueprintKind::Goal => Some(self.goal.placement()),
+        };
+    }
+
+    pub fn set_placement(&mut self, blueprint_ref: BlueprintRef, placement: Placement) {
+        let index = blueprint_ref.index;
+        match blueprint_ref.kind {
+            BlueprintKind::Wall => {
+                if let Some(wall) = self.walls.0.get_mut(index) {
+                    wall.set_placement(placement);
+                }
+            }
+            BlueprintKind::Prop => {
+                if let Some(PropBlueprint::BoxBlueprint(box_blueprint)) =
+                    self.props.0.get_mut(index)
+                {
+                    box_blueprint.set_placement(placement);
+                }
+            }
+            BlueprintKind::Input => {
+                if let Some(InputBlueprint::Button(button_blueprint)) = self.inputs.0.get_mut(index)
+                {
+                    button_blueprint.set_placement(placement);
+                }
+            }
+            BlueprintKind::Output => {
+                if let Some(OutputBluePrint::Door(door_blueprint)) = self.outputs.0.get_mut(index) {
+                    door_blueprint.set_placement(placement);
+                }
+            }
+            BlueprintKind::Goal => self.goal.set_placement(placement),
+        }
+    }
+
+    // Buttons have their off material first and their on material second
+    pub fn material_keys_mut(&mut self, blueprint_ref: BlueprintRef) -> Vec<&mut String> {
+        let index = blueprint_ref.index;
+        return match blueprint_ref.kind {
+            BlueprintKind::Wall => self
+                .walls
+                .0
+                .get_mut(index)
+                .map_or(vec![], |wall| wall.material_keys_mut()),
+            BlueprintKind::Prop => self
+                .props
+                .0
+                .get_mut(index)
+                .map_or(vec![], |prop| match prop {
+                    PropBlueprint::BoxBlueprint(box_blueprint) => box_blueprint.material_keys_mut(),
+                }),
+            BlueprintKind::Input => {
+                self.inputs
+                    .0
+                    .get_mut(index)
+                    .map_or(vec![], |input| match input {
+                        InputBlueprint::Button(button_blueprint) => {
+                            button_blueprint.material_keys_mut()
+                        }
+                    })
+            }
+            BlueprintKind::Output => {
+                self.outputs
+                    .0
+                    .get_mut(index)
+                    .map_or(vec![], |output| match output {
+                        OutputBluePrint::Door(door_blueprint) => door_blueprint.material_keys_mut(),
+                    })
+            }
+            BlueprintKind::Goal => self.goal.material_keys_mut(),
+        };
+    }
+
+    pub fn material_names(&self) -> Vec<String> {
+        let mut names = self
+            .level_materials
+            .present
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        return names;
+    }
+
+    // Adds a new wall, prop, input or output at pos, the goal is only ever moved. New inputs
+    // and outputs get their own node in the logic graph so the level stays valid
+    pub fn add(&mut self, kind: BlueprintKind, pos: Vec2) -> BlueprintRef {
+        let index = match kind {
+            BlueprintKind::Wall => {
+                let material = self.material_or_default("wall");
+                self.walls
+                    .0
+                    .push(WallBluePrint::new(pos, 0.0, vec2(100.0, 5.0), &material));
+                self.walls.0.len() - 1
+            }
+            BlueprintKind::Prop => {
+                let material = self.material_or_default("box");
+                self.props
+                    .0
+                    .push(PropBlueprint::BoxBlueprint(BoxBlueprint::new(
+                        pos,
+                        0.0,
+                        vec2(20.0, 20.0),
+                        &material,
+                    )));
+                self.props.0.len() - 1
+            }
+            BlueprintKind::Input => {
+                let on_material = self.material_or_default("button_on");
+                let off_material = self.material_or_default("button_off");
+                self.inputs
+                    .0
+                    .push(InputBlueprint::Button(ButtonBlueprint::new(
+                        pos,
+                        10.0,
+                        ButtonType::PressButton,
+                        &on_material,
+                        &off_material,
+                    )));
+                self.logic_graph.push_input_node();
+                self.inputs.0.len() - 1
+            }
+            BlueprintKind::Output => {
+                let material = self.material_or_default("door");
+                self.outputs
+                    .0
+                    .push(OutputBluePrint::Door(DoorBlueprint::new(
+                        pos,
+                        0.0,
+                        vec2(5.0, 100.0),
+                        &material,
+                    )));
+                self.logic_graph.push_output_node();
+                self.outputs.0.len() - 1
+            }
+            BlueprintKind::Goal => {
+                let mut placement = self.goal.placement();
+                placement.pos = pos;
+                self.goal.set_placement(placement);
+                0
+            }
         };
+        return BlueprintRef::new(kind, index);
+    }
+
+    // False for the goal, every level needs one. Removed inputs and outputs take their unnamed
+    // slots out of the logic graph
+    pub fn remove(&mut self, blueprint_ref: BlueprintRef) -> bool {
+        let index = blueprint_ref.index;
+        match blueprint_ref.kind {
+            BlueprintKind::Wall if index < self.walls.0.len() => {
+                self.walls.0.remove(index);
+            }
+            BlueprintKind::Prop if index < self.props.0.len() => {
+                self.props.0.remove(index);
+            }
+            BlueprintKind::Input if index < self.inputs.0.len() => {
+                let unnamed_before =
+                    count_unnamed(self.inputs.0[..index].iter().map(|i| i.slots()));
+                let removed = count_unnamed([self.inputs.0.remove(index).slots()].into_iter());
+                for _ in 0..removed {
+                    self.logic_graph.remove_input_node(unnamed_before);
+                }
+            }
+            BlueprintKind::Output if index < self.outputs.0.len() => {
+                let unnamed = count_unnamed(self.outputs.0.iter().map(|o| o.slots()));
+                let unnamed_before =
+                    count_unnamed(self.outputs.0[..index].iter().map(|o| o.slots()));
+                let removed = count_unnamed([self.outputs.0.remove(index).slots()].into_iter());
+                for _ in 0..removed {
+                    self.logic_graph
+                        .remove_output_node(unnamed - unnamed_before - removed);
+                }
+            }
+            _ => return false,
+        }
+        return true;
+    }
+
+    // Missing material keys would panic on spawn, so they are added with a neutral grey
+    fn material_or_default(&mut self, key: &str) -> String {
+        self.level_materials
+            .present
+            .entry(key.to_string())
+            .or_insert_with(|| HSL::new(0.0, 0.0, 0.5));
+        return key.to_string();
     }
 
     pub fn spawn(
@@ -99,47 +378,64 @@ impl LevelBlueprint {
         materials: &mut Assets<ColorMaterial>,
     ) -> Level {
         self.setup_level_material_handles(level_material_handles, materials);
-        let materials = &level_material_handles.0;
+        let materials = &level_material_handles.handles;
         let root = LevelRootBundle::new().spawn(commands);
         *player_pos = self.player.0.extend(PLAYER_Z_OFFSET);
 
-        for wall in self.walls.0 {
+        for (i, wall) in self.walls.0.into_iter().enumerate() {
             let wall_ent = wall.spawn(materials, &handles.square_mesh, commands);
             commands.get_entity(root).unwrap().add_child(wall_ent);
+            commands
+                .entity(wall_ent)
+                .insert(BlueprintRef::new(BlueprintKind::Wall, i));
         }
-        for prop in self.props.0 {
+        for (i, prop) in self.props.0.into_iter().enumerate() {
             let prop_ent = match prop {
                 PropBlueprint::BoxBlueprint(box_blueprint) => {
                     box_blueprint.spawn(commands, materials, &handles.square_mesh)
                 }
             };
             commands.get_entity(root).unwrap().add_child(prop_ent);
+            commands
+                .entity(prop_ent)
+                .insert(BlueprintRef::new(BlueprintKind::Prop, i));
         }
 
         let mut input_ents = Vec::with_capacity(self.inputs.0.len());
-        for input in self.inputs.0 {
+        for (i, input) in self.inputs.0.into_iter().enumerate() {
+            let slots = input.slots();
             let input_ent = match input {
                 InputBlueprint::Button(button_blueprint) => {
                     button_blueprint.spawn(commands, materials, &handles.circle_mesh)
                 }
             };
             commands.get_entity(root).unwrap().add_child(input_ent);
-            input_ents.push(input_ent);
+            commands
+                .entity(input_ent)
+                .insert(BlueprintRef::new(BlueprintKind::Input, i));
+            input_ents.push((input_ent, slots));
         }
 
         let mut output_ents = Vec::with_capacity(self.outputs.0.len());
-        for output in self.outputs.0 {
+        for (i, output) in self.outputs.0.into_iter().enumerate() {
+            let slots = output.slots();
             let output_ent = match output {
                 OutputBluePrint::Door(door) => {
                     door.spawn(commands, materials, &handles.square_mesh)
                 }
             };
             commands.get_entity(root).unwrap().add_child(output_ent);
-            output_ents.push(output_ent)
+            commands
+                .entity(output_ent)
+                .insert(BlueprintRef::new(BlueprintKind::Output, i));
+            output_ents.push((output_ent, slots));
         }
 
         let goal_ent = self.goal.spawn(commands, materials, &handles.square_mesh);
         commands.get_entity(root).unwrap().add_child(goal_ent);
+        commands
+            .entity(goal_ent)
+            .insert(BlueprintRef::new(BlueprintKind::Goal, 0));
 
         return Level::new(root, self.logic_graph, input_ents, output_ents);
     }
@@ -149,39 +445,97 @@ impl LevelBlueprint {
         level_material_handles: &mut LevelMaterialHandles,
         materials: &mut Assets<ColorMaterial>,
     ) {
-        level_material_handles.0.clear();
-        for (key, color) in self.level_materials.0.iter() {
+        level_material_handles.handles.clear();
+        level_material_handles.present.clear();
+        level_material_handles.past.clear();
+        for (key, color) in self.level_materials.present.iter() {
             let color_handle = materials.add(color.to_color_mat());
-            level_material_handles.0.insert(key.clone(), color_handle);
+            level_material_handles
+                .handles
+                .insert(key.clone(), color_handle);
+            level_material_handles
+                .present
+                .insert(key.clone(), color.to_color());
+        }
+        for (key, color) in self.level_materials.past.iter() {
+            level_material_handles
+                .past
+                .insert(key.clone(), color.to_color());
         }
     }
 }
 
-#[derive(Deserialize, Serialize)]
+fn count_unnamed(slots: impl Iterator<Item = Vec<Option<String>>>) -> usize {
+    return slots.flatten().filter(|slot| slot.is_none()).count();
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlueprintKind {
+    Wall,
+    Prop,
+    Input,
+    Output,
+    Goal,
+}
+
+// Ties a spawned entity back to the blueprint entry it was spawned from
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlueprintRef {
+    pub kind: BlueprintKind,
+    pub index: usize,
+}
+impl BlueprintRef {
+    pub fn new(kind: BlueprintKind, index: usize) -> Self {
+        return Self { kind, index };
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
 struct PlayerBlueprint(Vec2);
-impl ConfigTag for PlayerBlueprint {}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct WallBlueprints(Vec<WallBluePrint>);
-impl ConfigTag for WallBlueprints {}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct PropBlueprints(Vec<PropBlueprint>);
-impl ConfigTag for PropBlueprints {}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct InputBlueprints(Vec<InputBlueprint>);
-impl ConfigTag for InputBlueprints {}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct OutputBlueprints(Vec<OutputBluePrint>);
-impl ConfigTag for OutputBlueprints {}
 
-impl ConfigTag for LogicGraph {}
+// The past palette lives under the "past" key and only needs the colours that change
+#[derive(Deserialize, Serialize, Clone)]
+pub struct LevelMaterials {
+    #[serde(flatten)]
+    present: HashMap<String, HSL>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    past: HashMap<String, HSL>,
+}
 
-#[derive(Deserialize, Serialize)]
-pub struct LevelMaterials(HashMap<String, HSL>);
-impl ConfigTag for LevelMaterials {}
+#[derive(Deserialize, Serialize, Clone)]
+struct TimeBlueprint {
+    max_loops: usize,
+}
 
 #[derive(Resource, Default)]
-pub struct LevelMaterialHandles(pub HashMap<String, Handle<ColorMaterial>>);
+pub struct LevelMaterialHandles {
+    pub handles: HashMap<String, Handle<ColorMaterial>>,
+    present: HashMap<String, Color>,
+    past: HashMap<String, Color>,
+}
+impl LevelMaterialHandles {
+    // Recolours the level's materials in place so every entity using them follows along
+    pub fn apply_palette(&self, is_past: bool, materials: &mut Assets<ColorMaterial>) {
+        for (key, handle) in self.handles.iter() {
+            let color = match (is_past, self.past.get(key)) {
+                (true, Some(past_color)) => past_color,
+                _ => &self.present[key],
+            };
+            if let Some(material) = materials.get_mut(handle) {
+                material.color = *color;
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use bevy::log::info;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    configuration::ConfigError,
+    consts::{
+        GOAL_FILE, INPUTS_FILE, LOGIC_GRAPH_FILE, MATERIALS_FILE, OUTPUTS_FILE, PLAYER_FILE,
+        PROPS_FILE, TIME_FILE, WALLS_FILE,
+    },
+};
+
+pub const LEVEL_FORMAT_VERSION: u64 = 1;
+
+pub fn read_json(path: &str) -> Result<Value, ConfigError> {
+    info!("Loading level file at {path}");
+    let file = File::open(path).map_err(|err| ConfigError::io(path, err))?;
+
+    return serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| ConfigError::json(path, err));
+}
+
+// Gathers the one file per section layout into a single document, these are format version 0
+pub fn read_split_level(dir: &str) -> Result<Value, ConfigError> {
+    let sections = [
+        ("player", PLAYER_FILE),
+        ("walls", WALLS_FILE),
+        ("props", PROPS_FILE),
+        ("inputs", INPUTS_FILE),
+        ("outputs", OUTPUTS_FILE),
+        ("goal", GOAL_FILE),
+        ("logic_graph", LOGIC_GRAPH_FILE),
+        ("materials", MATERIALS_FILE),
+    ];
+
+    let mut doc = Map::new();
+    doc.insert("format_version".to_string(), json!(0));
+    for (key, file) in sections {
+        doc.insert(key.to_string(), read_json(&format!("{}/{}", dir, file))?);
+    }
+
+    let time_path = format!("{}/{}", dir, TIME_FILE);
+    if Path::new(&time_path).exists() {
+        doc.insert("time".to_string(), read_json(&time_path)?);
+    }
+    return Ok(Value::Object(doc));
+}
+
+// Upgrades a level document one version at a time until it reaches LEVEL_FORMAT_VERSION
+pub fn migrate(path: &str, doc: &mut Value) -> Result<(), ConfigError> {
+    let Some(doc) = doc.as_object_mut() else {
+        return Err(ConfigError::NotAnObject {
+            path: path.to_string(),
+        });
+    };
+    let mut version = doc
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version > LEVEL_FORMAT_VERSION {
+        return Err(ConfigError::UnsupportedVersion {
+            path: path.to_string(),
+            version,
+        });
+    }
+
+    while version < LEVEL_FORMAT_VERSION {
+        match version {
+            0 => migrate_v0(doc),
+            _ => {
+                return Err(ConfigError::UnsupportedVersion {
+                    path: path.to_string(),
+                    version,
+                })
+            }
+        }
+        version += 1;
+        doc.insert("format_version".to_string(), json!(version));
+    }
+    return Ok(());
+}
+
+// Version 0 levels predate stacked loops and could leave the time section out
+fn migrate_v0(doc: &mut Map<String, Value>) {
+    doc.entry("time")
+        .or_insert_with(|| json!({ "max_loops": 1 }));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{migrate, LEVEL_FORMAT_VERSION};
+    use crate::configuration::ConfigError;
+
+    #[test]
+    fn migrate_v0_level() {
+        let mut doc = json!({ "format_version": 0, "player": [0.0, 0.0] });
+        migrate("level", &mut doc).unwrap();
+        assert!(doc["format_version"] == json!(LEVEL_FORMAT_VERSION));
+        assert!(doc["time"] == json!({ "max_loops": 1 }));
+        assert!(doc["player"] == json!([0.0, 0.0]));
+
+        // Split levels without a version are version 0
+        let mut doc = json!({ "time": { "max_loops": 3 } });
+        migrate("level", &mut doc).unwrap();
+        assert!(doc["format_version"] == json!(LEVEL_FORMAT_VERSION));
+        assert!(doc["time"] == json!({ "max_loops": 3 }));
+    }
+
+    #[test]
+    fn migrate_current_level() {
+        let doc = json!({ "format_version": LEVEL_FORMAT_VERSION, "time": { "max_loops": 2 } });
+        let mut migrated = doc.clone();
+        migrate("level", &mut migrated).unwrap();
+        assert!(migrated == doc);
+    }
+
+    #[test]
+    fn migrate_unsupported_level() {
+        let mut doc = json!({ "format_version": LEVEL_FORMAT_VERSION + 1 });
+        assert!(matches!(
+            migrate("level", &mut doc),
+            Err(ConfigError::UnsupportedVersion { version, .. }) if version == LEVEL_FORMAT_VERSION + 1
+        ));
+
+        let mut doc = json!([{ "format_version": 0 }]);
+        assert!(matches!(
+            migrate("level", &mut doc),
+            Err(ConfigError::NotAnObject { .. })
+        ));
+    }
+}
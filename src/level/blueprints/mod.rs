@@ -1,6 +1,22 @@
+use bevy::math::Vec2;
+
 pub mod goal;
 pub mod input;
 pub mod level;
+pub mod migrate;
 pub mod output;
 pub mod props;
 pub mod wall;
+
+// Where a blueprint sits in the level, shape is the size its unit mesh gets scaled to
+#[derive(Clone, Copy)]
+pub struct Placement {
+    pub pos: Vec2,
+    pub z_rot: f32,
+    pub shape: Vec2,
+}
+impl Placement {
+    pub fn new(pos: Vec2, z_rot: f32, shape: Vec2) -> Self {
+        return Self { pos, z_rot, shape };
+    }
+}
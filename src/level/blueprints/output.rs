@@ -8,19 +8,37 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::level::bundles::output::DoorBundle;
+use crate::level::{
+    bundles::output::{DoorBundle, SlidingDoorBundle},
+    output::DoorType,
+};
+
+use super::Placement;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub enum OutputBluePrint {
     Door(DoorBlueprint),
 }
+impl OutputBluePrint {
+    // One entry per state the spawned Output pops, naming the node it reads. None falls back
+    // to the next of the logic graph's output_nodes
+    pub fn slots(&self) -> Vec<Option<String>> {
+        return match self {
+            OutputBluePrint::Door(door_blueprint) => vec![door_blueprint.node.clone()],
+        };
+    }
+}
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct DoorBlueprint {
     pos: Vec2,
     z_rot: f32,
     shape: Vec2,
     material_key: String,
+    #[serde(default)]
+    door_type: DoorType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node: Option<String>,
 }
 impl DoorBlueprint {
     pub fn new(pos: Vec2, z_rot: f32, shape: Vec2, material_key: &str) -> Self {
@@ -29,22 +47,48 @@ impl DoorBlueprint {
             z_rot,
             shape,
             material_key: material_key.to_string(),
+            door_type: DoorType::Door,
+            node: None,
         };
     }
 
+    pub fn placement(&self) -> Placement {
+        return Placement::new(self.pos, self.z_rot, self.shape);
+    }
+
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.pos = placement.pos;
+        self.z_rot = placement.z_rot;
+        self.shape = placement.shape;
+    }
+
+    pub fn material_keys_mut(&mut self) -> Vec<&mut String> {
+        return vec![&mut self.material_key];
+    }
+
     pub fn spawn(
         self,
         commands: &mut Commands,
         materials: &HashMap<String, Handle<ColorMaterial>>,
         mesh: &Mesh2dHandle,
     ) -> Entity {
-        return DoorBundle::new(
-            &materials[&self.material_key],
-            mesh,
-            self.pos,
-            self.z_rot,
-            self.shape,
-        )
-        .spawn(commands);
+        return match self.door_type {
+            DoorType::Door => DoorBundle::new(
+                &materials[&self.material_key],
+                mesh,
+                self.pos,
+                self.z_rot,
+                self.shape,
+            )
+            .spawn(commands),
+            DoorType::SlidingDoor => SlidingDoorBundle::new(
+                &materials[&self.material_key],
+                mesh,
+                self.pos,
+                self.z_rot,
+                self.shape,
+            )
+            .spawn(commands),
+        };
     }
 }
@@ -8,19 +8,26 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::level::bundles::props::BoxBundle;
+use crate::{
+    consts::PIXELS_PER_METER,
+    level::{bundles::props::BoxBundle, time_shift::TimeBehaviour},
+};
+
+use super::Placement;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub enum PropBlueprint {
     BoxBlueprint(BoxBlueprint),
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct BoxBlueprint {
     pos: Vec2,
     z_rot: f32,
     shape: Vec2,
     material_key: String,
+    #[serde(default)]
+    time_behaviour: TimeBehaviour,
 }
 impl BoxBlueprint {
     pub fn new(pos: Vec2, z_rot: f32, shape: Vec2, material_key: &str) -> Self {
@@ -29,9 +36,29 @@ impl BoxBlueprint {
             z_rot,
             shape,
             material_key: material_key.to_string(),
+            time_behaviour: TimeBehaviour::Drifting,
         };
     }
 
+    pub fn placement(&self) -> Placement {
+        return Placement::new(self.pos, self.z_rot, self.shape);
+    }
+
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.pos = placement.pos;
+        self.z_rot = placement.z_rot;
+        self.shape = placement.shape;
+    }
+
+    pub fn material_keys_mut(&mut self) -> Vec<&mut String> {
+        return vec![&mut self.material_key];
+    }
+
+    // The mass rapier gives the box at unit density
+    pub fn weight(&self) -> f32 {
+        return self.shape.x * self.shape.y / (PIXELS_PER_METER * PIXELS_PER_METER);
+    }
+
     pub fn spawn(
         self,
         commands: &mut Commands,
@@ -46,6 +73,7 @@ impl BoxBlueprint {
             self.z_rot,
             10.0,
             1.0,
+            self.time_behaviour,
         )
         .spawn(commands);
     }
@@ -8,14 +8,18 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::level::bundles::wall::WallBundle;
+use crate::level::{bundles::wall::WallBundle, time_shift::TimeBehaviour};
 
-#[derive(Deserialize, Serialize)]
+use super::Placement;
+
+#[derive(Deserialize, Serialize, Clone)]
 pub struct WallBluePrint {
     pos: Vec2,
     z_rot: f32,
     shape: Vec2,
     material_key: String,
+    #[serde(default)]
+    time_behaviour: TimeBehaviour,
 }
 impl WallBluePrint {
     pub fn new(pos: Vec2, z_rot: f32, shape: Vec2, material_key: &str) -> Self {
@@ -24,9 +28,24 @@ impl WallBluePrint {
             z_rot,
             shape,
             material_key: material_key.to_string(),
+            time_behaviour: TimeBehaviour::Drifting,
         };
     }
 
+    pub fn placement(&self) -> Placement {
+        return Placement::new(self.pos, self.z_rot, self.shape);
+    }
+
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.pos = placement.pos;
+        self.z_rot = placement.z_rot;
+        self.shape = placement.shape;
+    }
+
+    pub fn material_keys_mut(&mut self) -> Vec<&mut String> {
+        return vec![&mut self.material_key];
+    }
+
     pub fn spawn(
         self,
         materials: &HashMap<String, Handle<ColorMaterial>>,
@@ -39,6 +58,7 @@ impl WallBluePrint {
             self.pos,
             self.z_rot,
             self.shape,
+            self.time_behaviour,
         )
         .spawn(commands);
     }
@@ -8,7 +8,7 @@ use bevy_rapier2d::prelude::{Collider, Sensor};
 
 use crate::{
     consts::INPUT_Z_OFFSET,
-    level::input::{PressButton, ToggleButton},
+    level::input::{Dial, PressButton, PressurePlate, ToggleButton},
 };
 
 #[derive(Bundle)]
@@ -61,6 +61,57 @@ impl PressButtonBundle {
     }
 }
 
+#[derive(Bundle)]
+pub struct PressurePlateBundle {
+    button_bundle: ButtonBundle,
+    pressure_plate: PressurePlate,
+}
+impl PressurePlateBundle {
+    pub fn new(
+        material: &Handle<ColorMaterial>,
+        mesh: &Mesh2dHandle,
+        radius: f32,
+        pos: Vec2,
+        on_key: &str,
+        off_key: &str,
+    ) -> Self {
+        return Self {
+            button_bundle: ButtonBundle::new(material, mesh, radius, pos),
+            pressure_plate: PressurePlate::new(on_key, off_key),
+        };
+    }
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        return commands.spawn(self).id();
+    }
+}
+
+#[derive(Bundle)]
+pub struct DialBundle {
+    button_bundle: ButtonBundle,
+    dial: Dial,
+}
+impl DialBundle {
+    pub fn new(
+        material: &Handle<ColorMaterial>,
+        mesh: &Mesh2dHandle,
+        radius: f32,
+        pos: Vec2,
+        steps: usize,
+        on_key: &str,
+        off_key: &str,
+    ) -> Self {
+        return Self {
+            button_bundle: ButtonBundle::new(material, mesh, radius, pos),
+            dial: Dial::new(steps, on_key, off_key),
+        };
+    }
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        return commands.spawn(self).id();
+    }
+}
+
 #[derive(Bundle)]
 pub struct ButtonBundle {
     material_mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
@@ -6,7 +6,10 @@ use bevy::{
 };
 use bevy_rapier2d::prelude::Collider;
 
-use crate::{consts::OUTPUT_Z_OFFSET, level::output::Door};
+use crate::{
+    consts::OUTPUT_Z_OFFSET,
+    level::output::{Door, SlidingDoor},
+};
 
 #[derive(Bundle)]
 pub struct DoorBundle {
@@ -42,3 +45,38 @@ impl DoorBundle {
         return commands.spawn(self).id();
     }
 }
+
+#[derive(Bundle)]
+pub struct SlidingDoorBundle {
+    material_mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
+    collider: Collider,
+    sliding_door: SlidingDoor,
+}
+impl SlidingDoorBundle {
+    pub fn new(
+        material: &Handle<ColorMaterial>,
+        mesh: &Mesh2dHandle,
+        pos: Vec2,
+        z_rot: f32,
+        shape: Vec2,
+    ) -> Self {
+        return Self {
+            material_mesh_bundle: MaterialMesh2dBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform {
+                    translation: pos.extend(OUTPUT_Z_OFFSET),
+                    rotation: Quat::from_rotation_z(z_rot),
+                    scale: shape.extend(1.0),
+                },
+                ..default()
+            },
+            collider: Collider::cuboid(0.5, 0.5),
+            sliding_door: SlidingDoor::new(pos, shape),
+        };
+    }
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        return commands.spawn(self).id();
+    }
+}
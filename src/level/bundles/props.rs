@@ -4,15 +4,21 @@ use bevy::{
     prelude::{default, Bundle, Commands, Entity, Transform},
     sprite::{ColorMaterial, MaterialMesh2dBundle, Mesh2dHandle},
 };
-use bevy_rapier2d::prelude::{Collider, Damping, LockedAxes, RigidBody, Velocity};
+use bevy_rapier2d::prelude::{
+    Collider, Damping, LockedAxes, ReadMassProperties, RigidBody, Velocity,
+};
 
-use crate::{consts::PROP_Z_OFFSET, level::time_shift::TimeShift};
+use crate::{
+    consts::PROP_Z_OFFSET,
+    level::time_shift::{TimeBehaviour, TimeShift},
+};
 
 #[derive(Bundle)]
 pub struct BoxBundle {
     material_mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
     physics_prop_bundle: PhysicsPropBundle,
     time_shift: TimeShift,
+    time_behaviour: TimeBehaviour,
 }
 impl BoxBundle {
     pub fn new(
@@ -23,6 +29,7 @@ impl BoxBundle {
         z_rot: f32,
         linear_damping: f32,
         angular_damping: f32,
+        time_behaviour: TimeBehaviour,
     ) -> Self {
         return Self {
             material_mesh_bundle: MaterialMesh2dBundle {
@@ -41,6 +48,7 @@ impl BoxBundle {
                 angular_damping,
             ),
             time_shift: TimeShift::new(pos),
+            time_behaviour,
         };
     }
     pub fn spawn(self, commands: &mut Commands) -> Entity {
@@ -55,6 +63,7 @@ pub struct PhysicsPropBundle {
     pub damping: Damping,
     pub locked_axis: LockedAxes,
     pub velocity: Velocity,
+    pub read_mass_properties: ReadMassProperties,
 }
 impl PhysicsPropBundle {
     pub fn new(collider: Collider, linear_damping: f32, angular_damping: f32) -> Self {
@@ -67,6 +76,7 @@ impl PhysicsPropBundle {
             },
             locked_axis: LockedAxes::ROTATION_LOCKED,
             velocity: Velocity::default(),
+            read_mass_properties: ReadMassProperties::default(),
         };
     }
 }
@@ -6,12 +6,13 @@ use bevy::{
 };
 use bevy_rapier2d::prelude::Collider;
 
-use crate::consts::WALL_Z_OFFSET;
+use crate::{consts::WALL_Z_OFFSET, level::time_shift::TimeBehaviour};
 
 #[derive(Bundle)]
 pub struct WallBundle {
     material_mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
     collider: Collider,
+    time_behaviour: TimeBehaviour,
 }
 impl WallBundle {
     pub fn new(
@@ -20,6 +21,7 @@ impl WallBundle {
         pos: Vec2,
         z_rot: f32,
         shape: Vec2,
+        time_behaviour: TimeBehaviour,
     ) -> Self {
         return Self {
             material_mesh_bundle: MaterialMesh2dBundle {
@@ -33,6 +35,7 @@ impl WallBundle {
                 ..default()
             },
             collider: Collider::cuboid(0.5, 0.5),
+            time_behaviour,
         };
     }
 
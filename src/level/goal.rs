@@ -1,8 +1,8 @@
 use bevy::{
-    app::{Plugin, Update},
-    prelude::{Entity, EventWriter, Query, Res, With},
+    app::{FixedUpdate, Plugin},
+    prelude::{Entity, Event, EventWriter, IntoSystemConfigs, Query, Res, With},
 };
-use bevy_rapier2d::plugin::RapierContext;
+use bevy_rapier2d::plugin::{PhysicsSet, RapierContext};
 
 use crate::player::player_bundle::Player;
 
@@ -11,15 +11,20 @@ use super::{bundles::goal::Goal, level_pack::ChangeLevelEvent};
 pub struct GoalPlugin;
 impl Plugin for GoalPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, update_goal);
+        app.add_event::<LevelCompleteEvent>()
+            .add_systems(FixedUpdate, update_goal.after(PhysicsSet::Writeback));
     }
 }
 
-fn update_goal(
+#[derive(Event)]
+pub struct LevelCompleteEvent;
+
+pub fn update_goal(
     mut goals: Query<(&mut Goal, Entity)>,
     player: Query<Entity, With<Player>>,
     rapier_context: Res<RapierContext>,
     mut ev_change_level: EventWriter<ChangeLevelEvent>,
+    mut ev_level_complete: EventWriter<LevelCompleteEvent>,
 ) {
     if let Ok(player_ent) = player.get_single() {
         if let Ok((mut goal, goal_ent)) = goals.get_single_mut() {
@@ -29,6 +34,7 @@ fn update_goal(
             if let Some(intersect) = rapier_context.intersection_pair(goal_ent, player_ent) {
                 if intersect {
                     goal.triggered = true;
+                    ev_level_complete.send(LevelCompleteEvent);
                     ev_change_level.send(ChangeLevelEvent::new(1));
                 }
             }
@@ -1,13 +1,20 @@
 use bevy::{
-    app::{Plugin, Update},
+    app::{FixedUpdate, Plugin},
     asset::Handle,
-    prelude::{Component, Entity, Query, Res},
+    prelude::{in_state, Component, Entity, IntoSystemConfigs, Query, Res},
     sprite::ColorMaterial,
 };
-use bevy_rapier2d::plugin::RapierContext;
+use bevy_rapier2d::{
+    dynamics::ReadMassProperties,
+    plugin::{PhysicsSet, RapierContext},
+};
 use serde::{Deserialize, Serialize};
 
-use super::blueprints::level::LevelMaterialHandles;
+use crate::ui::ui_manager::GameState;
+
+use super::{
+    blueprints::level::LevelMaterialHandles, logic_graph::Signal, manager::update_level_state,
+};
 
 pub struct InputPlugin;
 impl Plugin for InputPlugin {
@@ -15,15 +22,29 @@ impl Plugin for InputPlugin {
         use bevy_trait_query::RegisterExt;
 
         app.register_component_as::<dyn Input, ToggleButton>()
-            .register_component_as::<dyn Input, PressButton>();
+            .register_component_as::<dyn Input, PressButton>()
+            .register_component_as::<dyn Input, PressurePlate>()
+            .register_component_as::<dyn Input, Dial>();
 
-        app.add_systems(Update, (update_toggle_button, update_press_button));
+        // Inputs read the contacts of the step that just ran so replays see them on the same tick
+        app.add_systems(
+            FixedUpdate,
+            (
+                update_toggle_button,
+                update_press_button,
+                update_pressure_plate,
+                update_dial,
+            )
+                .after(PhysicsSet::Writeback)
+                .before(update_level_state)
+                .run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
 #[bevy_trait_query::queryable]
 pub trait Input {
-    fn append_state(&self, vec: &mut Vec<bool>);
+    fn append_state(&self, vec: &mut Vec<Signal>);
     fn get_n(&self) -> usize;
 }
 
@@ -31,10 +52,12 @@ pub trait Input {
 pub enum InputType {
     ButtonType,
 }
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub enum ButtonType {
     ToggleButton,
     PressButton,
+    PressurePlate,
+    Dial { steps: usize },
 }
 
 #[derive(Component, Deserialize, Serialize)]
@@ -55,8 +78,8 @@ impl ToggleButton {
     }
 }
 impl Input for ToggleButton {
-    fn append_state(&self, vec: &mut Vec<bool>) {
-        vec.push(self.state);
+    fn append_state(&self, vec: &mut Vec<Signal>) {
+        vec.push(Signal::Bool(self.state));
     }
     fn get_n(&self) -> usize {
         return 1;
@@ -73,8 +96,8 @@ pub fn update_toggle_button(
             if !button.prev_state && cur_collider_state {
                 button.state = !button.state;
                 match button.state {
-                    true => *color = level_material_handles.0[&button.on_key].clone(),
-                    false => *color = level_material_handles.0[&button.off_key].clone(),
+                    true => *color = level_material_handles.handles[&button.on_key].clone(),
+                    false => *color = level_material_handles.handles[&button.off_key].clone(),
                 }
             }
             button.prev_state = cur_collider_state;
@@ -98,8 +121,8 @@ impl PressButton {
     }
 }
 impl Input for PressButton {
-    fn append_state(&self, vec: &mut Vec<bool>) {
-        vec.push(self.state);
+    fn append_state(&self, vec: &mut Vec<Signal>) {
+        vec.push(Signal::Bool(self.state));
     }
     fn get_n(&self) -> usize {
         return 1;
@@ -116,9 +139,113 @@ pub fn update_press_button(
         if button.state != new_state {
             button.state = new_state;
             match button.state {
-                true => *color = level_material_handles.0[&button.on_key].clone(),
-                false => *color = level_material_handles.0[&button.off_key].clone(),
+                true => *color = level_material_handles.handles[&button.on_key].clone(),
+                false => *color = level_material_handles.handles[&button.off_key].clone(),
+            }
+        }
+    }
+}
+
+// Reports the total mass of the bodies resting on it
+#[derive(Component, Deserialize, Serialize)]
+pub struct PressurePlate {
+    weight: f32,
+    on_key: String,
+    off_key: String,
+}
+impl PressurePlate {
+    pub fn new(on_key: &str, off_key: &str) -> Self {
+        return Self {
+            weight: 0.0,
+            on_key: on_key.to_string(),
+            off_key: off_key.to_string(),
+        };
+    }
+}
+impl Input for PressurePlate {
+    fn append_state(&self, vec: &mut Vec<Signal>) {
+        vec.push(Signal::Analog(self.weight));
+    }
+    fn get_n(&self) -> usize {
+        return 1;
+    }
+}
+
+pub fn update_pressure_plate(
+    rapier_context: Res<RapierContext>,
+    mut plates: Query<(Entity, &mut PressurePlate, &mut Handle<ColorMaterial>)>,
+    bodies: Query<&ReadMassProperties>,
+    level_material_handles: Res<LevelMaterialHandles>,
+) {
+    for (ent, mut plate, mut color) in plates.iter_mut() {
+        let weight = rapier_context
+            .intersection_pairs_with(ent)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .filter_map(|(a, b, _)| bodies.get(if a == ent { b } else { a }).ok())
+            .map(|mass_props| mass_props.get().mass)
+            .sum::<f32>();
+        if plate.weight != weight {
+            if (plate.weight > 0.0) != (weight > 0.0) {
+                match weight > 0.0 {
+                    true => *color = level_material_handles.handles[&plate.on_key].clone(),
+                    false => *color = level_material_handles.handles[&plate.off_key].clone(),
+                }
+            }
+            plate.weight = weight;
+        }
+    }
+}
+
+// Steps through steps + 1 evenly spaced values from 0 to 1 each time it is stepped on, wrapping
+// back to 0 after 1
+#[derive(Component, Deserialize, Serialize)]
+pub struct Dial {
+    step: usize,
+    steps: usize,
+    prev_state: bool,
+    on_key: String,
+    off_key: String,
+}
+impl Dial {
+    pub fn new(steps: usize, on_key: &str, off_key: &str) -> Self {
+        return Self {
+            step: 0,
+            steps: steps.max(1),
+            prev_state: false,
+            on_key: on_key.to_string(),
+            off_key: off_key.to_string(),
+        };
+    }
+
+    fn value(&self) -> f32 {
+        return self.step as f32 / self.steps as f32;
+    }
+}
+impl Input for Dial {
+    fn append_state(&self, vec: &mut Vec<Signal>) {
+        vec.push(Signal::Analog(self.value()));
+    }
+    fn get_n(&self) -> usize {
+        return 1;
+    }
+}
+
+pub fn update_dial(
+    rapier_context: Res<RapierContext>,
+    mut dials: Query<(Entity, &mut Dial, &mut Handle<ColorMaterial>)>,
+    level_material_handles: Res<LevelMaterialHandles>,
+) {
+    for (ent, mut dial, mut color) in dials.iter_mut() {
+        let cur_collider_state = rapier_context.intersection_pairs_with(ent).count() > 0;
+        if dial.prev_state != cur_collider_state {
+            if !dial.prev_state && cur_collider_state {
+                dial.step = (dial.step + 1) % (dial.steps + 1);
+                match dial.step > 0 {
+                    true => *color = level_material_handles.handles[&dial.on_key].clone(),
+                    false => *color = level_material_handles.handles[&dial.off_key].clone(),
+                }
             }
+            dial.prev_state = cur_collider_state;
         }
     }
 }
@@ -10,17 +10,28 @@ pub struct Level {
     outputs: Vec<Entity>,
 }
 impl Level {
+    // inputs and outputs pair each entity with the logic graph node ids its states are wired to
     pub fn new(
         root: Entity,
-        logic_graph: LogicGraph,
-        inputs: Vec<Entity>,
-        outputs: Vec<Entity>,
+        mut logic_graph: LogicGraph,
+        inputs: Vec<(Entity, Vec<Option<String>>)>,
+        outputs: Vec<(Entity, Vec<Option<String>>)>,
     ) -> Self {
+        let input_slots = inputs
+            .iter()
+            .flat_map(|(_, s)| s.clone())
+            .collect::<Vec<_>>();
+        let output_slots = outputs
+            .iter()
+            .flat_map(|(_, s)| s.clone())
+            .collect::<Vec<_>>();
+        logic_graph.compile(&input_slots, &output_slots);
+
         return Self {
             root,
             logic_graph,
-            inputs,
-            outputs,
+            inputs: inputs.into_iter().map(|(ent, _)| ent).collect(),
+            outputs: outputs.into_iter().map(|(ent, _)| ent).collect(),
         };
     }
 
@@ -32,6 +43,7 @@ impl Level {
         &mut self,
         inputs: &Query<One<&dyn Input>>,
         outputs: &mut Query<One<&mut dyn Output>>,
+        delta: f32,
     ) {
         if self.logic_graph.has_no_logic() {
             return;
@@ -44,7 +56,7 @@ impl Level {
             }
         }
 
-        let mut output = self.logic_graph.process(input_vec);
+        let mut output = self.logic_graph.process(input_vec, delta);
         // println!("{:?}", output);
         for output_ent in self.outputs.iter() {
             if let Ok(mut output_component) = outputs.get_mut(*output_ent) {
@@ -1,5 +1,7 @@
 use std::{
+    fmt::Display,
     fs::{self},
+    path::Path,
     usize,
 };
 
@@ -7,78 +9,230 @@ use bevy::{
     app::{Plugin, PreStartup, Update},
     asset::Assets,
     input::ButtonInput,
+    log::{error, info},
     math::Vec2,
     prelude::{
-        Commands, Event, EventReader, EventWriter, KeyCode, Query, Res, ResMut, Resource,
-        Transform, With,
+        in_state, Commands, Event, EventReader, EventWriter, IntoSystemConfigs, KeyCode, NextState,
+        Query, Res, ResMut, Resource, Transform, With,
     },
     sprite::ColorMaterial,
 };
 use bevy_rapier2d::prelude::Velocity;
 
 use crate::{
-    configuration::{key_bindings::KeyBinds, level::LevelConfig, Config},
+    configuration::{key_bindings::KeyBinds, level::LevelConfig, Config, ConfigError},
+    consts::PACK_MANIFEST_FILE,
     handles::Handles,
     player::player_bundle::Player,
+    progress::Progress,
+    ui::{
+        error_display::ErrorEvent,
+        events::{LoadLevelEvent, LoadLevelPackEvent},
+        stopwatch_display::ResetStopwatchEvent,
+        ui_manager::GameState,
+    },
 };
 
 use super::{
     blueprints::level::{LevelBlueprint, LevelMaterialHandles},
+    logic_graph::LogicGraphError,
     manager::LevelManager,
-    time_shift::{TimeShiftEvent, TimeState},
+    pack_manifest::{LevelMeta, PackManifest},
+    time_shift::TimeState,
 };
 
 pub struct LevelPackPlugin;
 impl Plugin for LevelPackPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_event::<ChangeLevelEvent>()
-            .add_systems(PreStartup, setup_default_pack)
-            .add_systems(Update, (read_change_level_event, write_change_level_event));
+            .add_event::<LevelStartedEvent>()
+            .add_systems(PreStartup, setup_level_packs)
+            .add_systems(
+                Update,
+                (
+                    read_load_level_pack_event.before(read_change_level_event),
+                    read_load_level_event.before(read_change_level_event),
+                    read_change_level_event,
+                    write_change_level_event.run_if(in_state(GameState::InGame)),
+                ),
+            );
     }
 }
-pub fn setup_default_pack(mut commands: Commands, level_config: Res<LevelConfig>) {
-    commands.insert_resource(LevelPack::new(&level_config.dir));
+pub fn setup_level_packs(mut commands: Commands, level_config: Res<LevelConfig>) {
+    let registry = LevelPackRegistry::scan(&level_config.dir, &level_config.packs_dir);
+    let default_pack = &registry.packs()[0];
+    commands.insert_resource(LevelPack::new(
+        &default_pack.dir,
+        default_pack.manifest.clone(),
+    ));
+    commands.insert_resource(registry);
+}
+
+// Reads a pack's manifest, packs without one get every level dir in name order
+pub fn load_manifest(dir: &str) -> PackManifest {
+    let mut levels = if let Ok(levels) = fs::read_dir(dir) {
+        levels
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|l| l.path().is_dir())
+            .map(|l| l.file_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+    } else {
+        vec![]
+    };
+    levels.sort();
+    info!("Found levels in {dir}: {levels:?}");
+
+    let manifest_path = format!("{}/{}", dir, PACK_MANIFEST_FILE);
+    return match PackManifest::load_cfg(&manifest_path) {
+        Ok(mut manifest) => {
+            manifest.retain_existing(&levels);
+            while let Some(cycle) = manifest.requires_cycle() {
+                let err = LevelError::RequiresCycle {
+                    pack: dir.to_string(),
+                    levels: cycle.clone(),
+                };
+                error!("{err}");
+                manifest.break_cycle(&cycle);
+            }
+            manifest
+        }
+        Err(err) => {
+            if !err.is_missing() {
+                error!("{err}, falling back to every level in {dir}");
+            }
+            let title = match Path::new(dir).file_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => dir.to_string(),
+            };
+            PackManifest::from_dirs(&title, levels)
+        }
+    };
 }
 
+pub struct PackEntry {
+    pub dir: String,
+    pub manifest: PackManifest,
+}
+
+// Every pack that can be switched to, the default pack from LevelConfig.dir comes first followed
+// by each dir under LevelConfig.packs_dir
 #[derive(Resource)]
-pub struct LevelPack {
-    dir: String,
-    levels: Vec<String>,
-    cur_i: isize,
+pub struct LevelPackRegistry {
+    packs: Vec<PackEntry>,
 }
-impl LevelPack {
-    pub fn new(dir: &str) -> Self {
-        let mut levels = if let Ok(levels) = fs::read_dir(dir) {
-            levels
+impl LevelPackRegistry {
+    pub fn scan(default_dir: &str, packs_dir: &str) -> Self {
+        let mut dirs = if let Ok(packs) = fs::read_dir(packs_dir) {
+            packs
                 .into_iter()
-                .filter(|l| l.as_ref().unwrap().metadata().unwrap().is_dir())
-                .map(|l| l.unwrap().file_name().to_str().unwrap().to_string())
+                .filter_map(|p| p.ok())
+                .filter(|p| p.path().is_dir())
+                .map(|p| format!("{}/{}", packs_dir, p.file_name().to_string_lossy()))
                 .collect::<Vec<String>>()
         } else {
             vec![]
         };
-        levels.sort();
-        println!("Loading levels: {levels:?}");
+        dirs.sort();
+        dirs.insert(0, default_dir.to_string());
+
+        let packs = dirs
+            .into_iter()
+            .map(|dir| PackEntry {
+                manifest: load_manifest(&dir),
+                dir,
+            })
+            .collect();
+        return Self { packs };
+    }
+
+    pub fn packs(&self) -> &[PackEntry] {
+        return &self.packs;
+    }
 
+    pub fn get(&self, dir: &str) -> Option<&PackEntry> {
+        return self.packs.iter().find(|pack| pack.dir == dir);
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelPack {
+    dir: String,
+    manifest: PackManifest,
+    cur_i: isize,
+}
+impl LevelPack {
+    pub fn new(dir: &str, manifest: PackManifest) -> Self {
         return Self {
             dir: dir.to_string(),
-            levels: levels,
+            manifest,
             cur_i: 0,
         };
     }
 
-    pub fn change_level(&mut self, delta: isize) -> LevelBlueprint {
+    pub fn dir(&self) -> &str {
+        return &self.dir;
+    }
+
+    pub fn levels(&self) -> &[LevelMeta] {
+        return &self.manifest.levels;
+    }
+
+    // None only for a pack without any levels
+    pub fn cur_level(&self) -> Option<&LevelMeta> {
+        return self.manifest.levels.get(self.cur_i as usize);
+    }
+
+    // A level is unlocked once every level it requires is complete
+    pub fn is_unlocked(&self, i: usize, is_complete: impl Fn(&str) -> bool) -> bool {
+        return self.manifest.levels[i]
+            .requires
+            .iter()
+            .all(|required| is_complete(required));
+    }
+
+    // Moving onto a locked level fails so it can be skipped, reloading the current one never does
+    pub fn change_level(
+        &mut self,
+        delta: isize,
+        is_complete: impl Fn(&str) -> bool,
+    ) -> Result<LevelBlueprint, LevelError> {
         self.set_cur_i(delta);
 
-        return LevelBlueprint::load_cfg(&format!(
-            "{}/{}",
-            self.dir, self.levels[self.cur_i as usize]
-        ));
+        let dir = self
+            .cur_level_dir()
+            .ok_or_else(|| LevelError::EmptyPack(self.dir.clone()))?;
+        if delta != 0 && !self.is_unlocked(self.cur_i as usize, is_complete) {
+            return Err(LevelError::Locked(dir));
+        }
+        let blueprint = LevelBlueprint::load_cfg(&dir)?;
+        blueprint.validate()?;
+        return Ok(blueprint);
+    }
+
+    pub fn select_level(&mut self, i: usize) {
+        if self.num_levels() == 0 {
+            return;
+        }
+        self.cur_i = i.min(self.num_levels() - 1) as isize;
+    }
+
+    pub fn num_levels(&self) -> usize {
+        return self.manifest.levels.len();
+    }
+
+    pub fn cur_level_dir(&self) -> Option<String> {
+        return self
+            .cur_level()
+            .map(|level| format!("{}/{}", self.dir, level.dir));
     }
 
     fn set_cur_i(&mut self, delta: isize) {
+        let num_levels = self.manifest.levels.len() as isize;
+        if num_levels == 0 {
+            return;
+        }
         self.cur_i += delta;
-        let num_levels = self.levels.len() as isize;
         if self.cur_i == num_levels {
             self.cur_i = 0;
         } else if self.cur_i == -1 {
@@ -87,31 +241,145 @@ impl LevelPack {
     }
 }
 
+#[derive(Debug)]
+pub enum LevelError {
+    Config(ConfigError),
+    LogicGraph(LogicGraphError),
+    EmptyPack(String),
+    Locked(String),
+    RequiresCycle { pack: String, levels: Vec<String> },
+}
+impl From<ConfigError> for LevelError {
+    fn from(err: ConfigError) -> Self {
+        return Self::Config(err);
+    }
+}
+impl From<LogicGraphError> for LevelError {
+    fn from(err: LogicGraphError) -> Self {
+        return Self::LogicGraph(err);
+    }
+}
+impl Display for LevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            LevelError::Config(err) => write!(f, "{err}"),
+            LevelError::LogicGraph(err) => write!(f, "{err}"),
+            LevelError::EmptyPack(dir) => write!(f, "Level pack {dir} has no levels"),
+            LevelError::Locked(dir) => write!(f, "Level {dir} is locked"),
+            LevelError::RequiresCycle { pack, levels } => write!(
+                f,
+                "Levels {} in pack {pack} require each other, the requirements between them are \
+                ignored",
+                levels.join(", ")
+            ),
+        };
+    }
+}
+
 #[derive(Event)]
 pub struct ChangeLevelEvent {
     delta: isize,
+    keep_player: bool,
+    enter_game: bool,
 }
 impl ChangeLevelEvent {
     pub fn new(delta: isize) -> Self {
-        return Self { delta };
+        return Self {
+            delta,
+            keep_player: false,
+            enter_game: false,
+        };
+    }
+
+    // Reloads the current level without moving the player back to the start
+    pub fn reload_in_place() -> Self {
+        return Self {
+            delta: 0,
+            keep_player: true,
+            enter_game: false,
+        };
+    }
+
+    // Starts the level picked from a menu, the game is only entered once it has loaded
+    pub fn start_selected() -> Self {
+        return Self {
+            delta: 0,
+            keep_player: false,
+            enter_game: true,
+        };
     }
 }
-fn read_change_level_event(
+// Sent once a level has been spawned from the start, reloading it in place doesn't count
+#[derive(Event)]
+pub struct LevelStartedEvent {
+    pub pack: String,
+    pub level: String,
+    pub content_hash: u64,
+}
+
+pub fn read_change_level_event(
     mut commands: Commands,
-    mut time_shift_ev: EventWriter<TimeShiftEvent>,
     mut change_level_ev: EventReader<ChangeLevelEvent>,
+    mut level_started_ev: EventWriter<LevelStartedEvent>,
+    mut reset_stopwatch_ev: EventWriter<ResetStopwatchEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
     mut player: Query<(&mut Transform, &mut Velocity), With<Player>>,
-    time_state: Res<TimeState>,
+    mut time_state: ResMut<TimeState>,
     mut level_manager: ResMut<LevelManager>,
     mut level_pack: ResMut<LevelPack>,
     mut level_material_handles: ResMut<LevelMaterialHandles>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     handles: Res<Handles>,
+    progress: Option<Res<Progress>>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
     for ev in change_level_ev.read() {
-        let blueprint = level_pack.change_level(ev.delta);
+        // Broken and locked levels are skipped in the direction of travel, a broken reload keeps
+        // the level. Without progress, as in headless runs, every level is unlocked
+        let pack_dir = level_pack.dir().to_string();
+        let is_complete = |level: &str| {
+            return progress
+                .as_ref()
+                .map_or(true, |progress| progress.is_complete(&pack_dir, level));
+        };
+        let mut delta = ev.delta;
+        let mut loaded = None;
+        for _ in 0..level_pack.num_levels() {
+            match level_pack.change_level(delta, is_complete) {
+                Ok(blueprint) => {
+                    loaded = Some(blueprint);
+                    break;
+                }
+                Err(LevelError::Locked(_)) => delta = delta.signum(),
+                Err(err) => {
+                    let dir = level_pack
+                        .cur_level_dir()
+                        .unwrap_or_else(|| level_pack.dir().to_string());
+                    let msg = format!("Unable to load level {dir}, {err}");
+                    error!("{msg}");
+                    error_ev.send(ErrorEvent::new(&msg));
+                    if delta == 0 {
+                        break;
+                    }
+                    delta = delta.signum();
+                }
+            }
+        }
+        let blueprint = match loaded {
+            Some(blueprint) => blueprint,
+            None => continue,
+        };
+
         let (mut transform, mut vel) = player.single_mut();
+        let prev_pos = transform.translation;
         vel.linvel = Vec2::ZERO;
+        *time_state = TimeState::new(blueprint.max_loops());
+        let content_hash = blueprint.content_hash();
+        // An in place reload carries on the same attempt, so the stopwatch keeps running along
+        // with the progress run
+        if !ev.keep_player {
+            reset_stopwatch_ev.send(ResetStopwatchEvent);
+        }
 
         level_manager.change_level(
             blueprint,
@@ -121,9 +389,60 @@ fn read_change_level_event(
             &mut level_material_handles,
             &mut materials,
         );
-        if time_state.is_present {
-            time_shift_ev.send(TimeShiftEvent);
+        if ev.keep_player {
+            transform.translation = prev_pos;
+        } else if let Some(level) = level_pack.cur_level() {
+            level_started_ev.send(LevelStartedEvent {
+                pack: level_pack.dir().to_string(),
+                level: level.dir.clone(),
+                content_hash,
+            });
+        }
+        if ev.enter_game {
+            next_state.set(GameState::InGame);
+        }
+    }
+}
+
+// Switches the active pack, levels are started from the level select screen
+fn read_load_level_pack_event(
+    mut load_level_pack_ev: EventReader<LoadLevelPackEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    registry: Res<LevelPackRegistry>,
+    mut level_pack: ResMut<LevelPack>,
+) {
+    for ev in load_level_pack_ev.read() {
+        // Empty packs are left out so the current level always exists
+        let msg = match registry.get(&ev.dir) {
+            Some(pack) if pack.manifest.levels.is_empty() => {
+                format!("Level pack {} has no levels", ev.dir)
+            }
+            Some(pack) => {
+                *level_pack = LevelPack::new(&pack.dir, pack.manifest.clone());
+                continue;
+            }
+            None => format!("Unknown level pack {}", ev.dir),
+        };
+        error!("{msg}");
+        error_ev.send(ErrorEvent::new(&msg));
+    }
+}
+
+fn read_load_level_event(
+    mut load_level_ev: EventReader<LoadLevelEvent>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut level_pack: ResMut<LevelPack>,
+) {
+    for ev in load_level_ev.read() {
+        if ev.id >= level_pack.num_levels() {
+            let msg = format!("Level pack {} has no level {}", level_pack.dir(), ev.id);
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+            continue;
         }
+        level_pack.select_level(ev.id);
+        change_level_ev.send(ChangeLevelEvent::start_selected());
     }
 }
 
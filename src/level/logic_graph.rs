@@ -1,114 +1,821 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct LogicGraph {
-    input_nodes: Vec<usize>,
-    output_nodes: Vec<usize>,
+    input_nodes: Vec<NodeRef>,
+    output_nodes: Vec<NodeRef>,
     nodes: Vec<Node>,
+    #[serde(skip)]
+    order: Vec<usize>,
+    #[serde(skip)]
+    inputs: Vec<usize>,
+    #[serde(skip)]
+    outputs: Vec<usize>,
 }
 impl LogicGraph {
     pub fn new(input_nodes: Vec<usize>, output_nodes: Vec<usize>, nodes: Vec<Node>) -> Self {
-        return Self {
-            input_nodes,
-            output_nodes,
+        let input_slots = vec![None; input_nodes.len()];
+        let output_slots = vec![None; output_nodes.len()];
+        let mut logic_graph = Self {
+            input_nodes: input_nodes.into_iter().map(NodeRef::Index).collect(),
+            output_nodes: output_nodes.into_iter().map(NodeRef::Index).collect(),
             nodes,
+            order: vec![],
+            inputs: vec![],
+            outputs: vec![],
         };
+        logic_graph.compile(&input_slots, &output_slots);
+        return logic_graph;
+    }
+
+    // Resolves node references to indices and wires the level's input and output slots to
+    // nodes. Slots naming a node id bind to that node, the rest take input_nodes and
+    // output_nodes in order
+    //
+    // Then sorts the nodes so every node comes after its in_nodes, process evaluates them in a
+    // single pass. Nodes that are part of a cycle or read from a missing node are left out
+    pub fn compile(&mut self, input_slots: &[Option<String>], output_slots: &[Option<String>]) {
+        let ids = self.node_ids();
+        let num_nodes = self.nodes.len();
+        let resolve = |node_ref: &NodeRef| node_ref.resolve(&ids, num_nodes);
+
+        let in_nodes = self.resolve_in_nodes(&ids);
+        let inputs = Self::wire(input_slots.iter(), &self.input_nodes)
+            .iter()
+            .filter_map(resolve)
+            .collect();
+        let outputs = Self::wire(output_slots.iter().rev(), &self.output_nodes)
+            .iter()
+            .filter_map(resolve)
+            .collect();
+        self.inputs = inputs;
+        self.outputs = outputs;
+        for (node, in_nodes) in self.nodes.iter_mut().zip(in_nodes) {
+            node.in_nodes = in_nodes;
+        }
+
+        let mut dependants = vec![vec![]; num_nodes];
+        let mut remaining = vec![0; num_nodes];
+        for (i, node) in self.nodes.iter().enumerate() {
+            for in_node in node.in_nodes.iter() {
+                match dependants.get_mut(*in_node) {
+                    Some(d) => d.push(i),
+                    None => remaining[i] = usize::MAX,
+                }
+                remaining[i] = remaining[i].saturating_add(1);
+            }
+        }
+
+        let mut ready = (0..num_nodes)
+            .filter(|i| remaining[*i] == 0)
+            .collect::<VecDeque<usize>>();
+        self.order = Vec::with_capacity(num_nodes);
+        while let Some(i) = ready.pop_front() {
+            self.order.push(i);
+            for dependant in dependants[i].iter() {
+                remaining[*dependant] -= 1;
+                if remaining[*dependant] == 0 {
+                    ready.push_back(*dependant);
+                }
+            }
+        }
+
+        self.reset();
+    }
+
+    // Outputs pop their state off the back of the processed vec so output slots are passed in
+    // reverse, the first output entity reads the last element
+    fn wire<'a>(
+        slots: impl Iterator<Item = &'a Option<String>>,
+        unnamed: &[NodeRef],
+    ) -> Vec<NodeRef> {
+        let mut unnamed = unnamed.iter();
+        return slots
+            .filter_map(|slot| match slot {
+                Some(id) => Some(NodeRef::Id(id.clone())),
+                None => unnamed.next().cloned(),
+            })
+            .collect();
+    }
+
+    fn node_ids(&self) -> HashMap<&str, usize> {
+        return self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| node.id.as_ref().map(|id| (id.as_str(), i)))
+            .collect();
+    }
+
+    // in_nodes of every node as indices, references that do not resolve become usize::MAX
+    fn resolve_in_nodes(&self, ids: &HashMap<&str, usize>) -> Vec<Vec<usize>> {
+        return self
+            .nodes
+            .iter()
+            .map(|node| {
+                node.in_refs
+                    .iter()
+                    .map(|r| r.resolve(ids, self.nodes.len()).unwrap_or(usize::MAX))
+                    .collect()
+            })
+            .collect();
+    }
+
+    // Puts every stateful node back to the initial value it was configured with
+    pub fn reset(&mut self) {
+        for node in self.nodes.iter_mut() {
+            node.state = node.op.initial_state();
+            node.memory = Memory {
+                prev_in: false,
+                count: node.op.initial_count(),
+                timer: 0.0,
+            };
+        }
+    }
+
+    // The editor adds and removes unnamed slots, graphs without logic have nothing to rewire.
+    // New inputs take the last input node, new outputs the first output node as outputs are
+    // wired in reverse
+    pub fn push_input_node(&mut self) {
+        if self.has_no_logic() {
+            return;
+        }
+        self.nodes.push(Node::new(Operator::None, vec![]));
+        self.input_nodes.push(NodeRef::Index(self.nodes.len() - 1));
+    }
+
+    pub fn push_output_node(&mut self) {
+        if self.has_no_logic() {
+            return;
+        }
+        self.nodes.push(Node::new(Operator::None, vec![]));
+        self.output_nodes
+            .insert(0, NodeRef::Index(self.nodes.len() - 1));
+    }
+
+    // The node itself stays in the graph as other nodes may still read it
+    pub fn remove_input_node(&mut self, i: usize) {
+        if i < self.input_nodes.len() {
+            self.input_nodes.remove(i);
+        }
+    }
+
+    pub fn remove_output_node(&mut self, i: usize) {
+        if i < self.output_nodes.len() {
+            self.output_nodes.remove(i);
+        }
     }
 
     pub fn has_no_logic(&self) -> bool {
         return self.nodes.is_empty();
     }
 
-    pub fn process(&mut self, input: Vec<bool>) -> Vec<bool> {
-        if input.len() != self.input_nodes.len() {
-            panic!();
+    pub fn num_inputs(&self) -> usize {
+        return self.inputs.len();
+    }
+
+    // Longest time any timed node waits for, running this long lets every timer settle
+    pub fn longest_timer(&self) -> f32 {
+        return self
+            .nodes
+            .iter()
+            .map(|node| match node.op {
+                Operator::Delay { secs } | Operator::Pulse { secs } | Operator::Hold { secs } => {
+                    secs
+                }
+                _ => 0.0,
+            })
+            .fold(0.0, f32::max);
+    }
+
+    // Everything process carries between calls, two graphs with equal keys behave the same
+    pub fn state_key(&self) -> Vec<u32> {
+        let mut key = Vec::with_capacity(self.nodes.len() * 5);
+        for node in self.nodes.iter() {
+            match node.state {
+                Signal::Bool(b) => key.extend([0, b as u32]),
+                Signal::Analog(v) => key.extend([1, v.to_bits()]),
+            }
+            key.extend([
+                node.memory.prev_in as u32,
+                node.memory.count as u32,
+                node.memory.timer.to_bits(),
+            ]);
         }
-        for i in 0..input.len() {
-            self.nodes[self.input_nodes[i]].state = input[i];
+        return key;
+    }
+
+    pub fn validate(
+        &self,
+        input_slots: &[Option<String>],
+        output_slots: &[Option<String>],
+    ) -> Result<(), LogicGraphError> {
+        let mut problems = vec![];
+        if self.has_no_logic() {
+            return Ok(());
         }
 
-        let num_out = self.output_nodes.len();
-        let mut output = Vec::with_capacity(num_out);
-        for i in 0..num_out {
-            let in_i = self.output_nodes[i];
-            self.update_node(in_i);
-            output.push(self.nodes[in_i].state);
+        let unnamed_inputs = input_slots.iter().filter(|s| s.is_none()).count();
+        if self.input_nodes.len() != unnamed_inputs {
+            problems.push(LogicGraphProblem::InputCountMismatch {
+                expected: unnamed_inputs,
+                found: self.input_nodes.len(),
+            });
+        }
+        let unnamed_outputs = output_slots.iter().filter(|s| s.is_none()).count();
+        if self.output_nodes.len() != unnamed_outputs {
+            problems.push(LogicGraphProblem::OutputCountMismatch {
+                expected: unnamed_outputs,
+                found: self.output_nodes.len(),
+            });
         }
 
-        self.reset_updated();
-        return output;
+        let ids = self.node_ids();
+        let num_nodes = self.nodes.len();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(id) = &node.id {
+                if ids[id.as_str()] != i {
+                    problems.push(LogicGraphProblem::DuplicateId { id: id.clone() });
+                }
+            }
+        }
+
+        let inputs = Self::wire(input_slots.iter(), &self.input_nodes);
+        for (slot, node) in inputs.into_iter().enumerate() {
+            match node.resolve(&ids, num_nodes) {
+                None => problems.push(LogicGraphProblem::MissingInputNode { slot, node }),
+                Some(i) if !matches!(self.nodes[i].op, Operator::None) => {
+                    problems.push(LogicGraphProblem::InputNodeHasOperator { node: i })
+                }
+                Some(_) => {}
+            }
+        }
+        let outputs = Self::wire(output_slots.iter().rev(), &self.output_nodes);
+        for (slot, node) in outputs.into_iter().enumerate() {
+            if node.resolve(&ids, num_nodes).is_none() {
+                problems.push(LogicGraphProblem::MissingOutputNode { slot, node });
+            }
+        }
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            for in_node in node.in_refs.iter() {
+                if in_node.resolve(&ids, num_nodes).is_none() {
+                    problems.push(LogicGraphProblem::MissingInNode {
+                        node: i,
+                        in_node: in_node.clone(),
+                    });
+                }
+            }
+            let (min, max) = node.op.arity();
+            let found = node.in_refs.len();
+            if found < min || found > max {
+                problems.push(LogicGraphProblem::WrongArity {
+                    node: i,
+                    min,
+                    max,
+                    found,
+                });
+            }
+        }
+
+        for cycle in Self::find_cycles(&self.resolve_in_nodes(&ids)) {
+            problems.push(LogicGraphProblem::Cycle { nodes: cycle });
+        }
+
+        return match problems.is_empty() {
+            true => Ok(()),
+            false => Err(LogicGraphError { problems }),
+        };
     }
 
-    fn update_node(&mut self, i: usize) {
-        let children = self.nodes[i].in_nodes.clone();
+    // Depth first search over in_nodes, every back edge closes a cycle
+    fn find_cycles(in_nodes: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let num_nodes = in_nodes.len();
+        let mut cycles = vec![];
+        // 0 = unvisited, 1 = on the stack, 2 = done
+        let mut visited = vec![0u8; num_nodes];
+
+        for start in 0..num_nodes {
+            if visited[start] != 0 {
+                continue;
+            }
+            visited[start] = 1;
+            let mut stack = vec![(start, 0)];
+
+            while let Some((node, next)) = stack.last().copied() {
+                let in_nodes = &in_nodes[node];
+                if next == in_nodes.len() {
+                    visited[node] = 2;
+                    stack.pop();
+                    continue;
+                }
+                stack.last_mut().unwrap().1 += 1;
 
-        for child_i in children.iter() {
-            self.update_node(*child_i);
+                let child = in_nodes[next];
+                if child >= num_nodes {
+                    continue;
+                }
+                match visited[child] {
+                    0 => {
+                        visited[child] = 1;
+                        stack.push((child, 0));
+                    }
+                    1 => {
+                        let from = stack.iter().position(|(n, _)| *n == child).unwrap();
+                        cycles.push(stack[from..].iter().map(|(n, _)| *n).collect());
+                    }
+                    _ => {}
+                }
+            }
         }
 
-        if self.nodes[i].updated {
-            return;
+        return cycles;
+    }
+
+    pub fn process<S: Into<Signal>>(&mut self, input: Vec<S>, delta: f32) -> Vec<Signal> {
+        if input.len() != self.inputs.len() {
+            panic!(
+                "Logic graph expects {} inputs but was given {}",
+                self.inputs.len(),
+                input.len()
+            );
+        }
+        for (i, signal) in input.into_iter().enumerate() {
+            self.nodes[self.inputs[i]].state = signal.into();
+        }
+
+        for order_i in 0..self.order.len() {
+            self.update_node(self.order[order_i], delta);
         }
 
+        return self.outputs.iter().map(|i| self.nodes[*i].state).collect();
+    }
+
+    fn update_node(&mut self, i: usize, delta: f32) {
+        let in_nodes = &self.nodes[i].in_nodes;
+        let values = in_nodes
+            .iter()
+            .map(|child_i| self.nodes[*child_i].state.as_f32());
+
         self.nodes[i].state = match self.nodes[i].op {
             Operator::None => return,
-            Operator::And => {
-                let mut s = true;
-                for child_i in children {
-                    s = s && self.nodes[child_i].state;
+            Operator::Constant { value } => Signal::Analog(value),
+            Operator::Sum => Signal::Analog(values.sum()),
+            Operator::Min => Signal::Analog(values.fold(f32::INFINITY, f32::min)),
+            Operator::Max => Signal::Analog(values.fold(f32::NEG_INFINITY, f32::max)),
+            Operator::Compare { comparison } => {
+                Signal::Bool(comparison.test(self.in_value(i, 0), self.in_value(i, 1)))
+            }
+            Operator::Threshold { value } => Signal::Bool(self.in_value(i, 0) >= value),
+            _ => Signal::Bool(self.update_logic_node(i, delta)),
+        };
+    }
+
+    // Operators that only look at whether their in_nodes are high
+    fn update_logic_node(&mut self, i: usize, delta: f32) -> bool {
+        let in_nodes = &self.nodes[i].in_nodes;
+        let state = self.nodes[i].state.as_bool();
+
+        return match self.nodes[i].op {
+            Operator::And => in_nodes
+                .iter()
+                .all(|child_i| self.nodes[*child_i].state.as_bool()),
+            Operator::Or => in_nodes
+                .iter()
+                .any(|child_i| self.nodes[*child_i].state.as_bool()),
+            Operator::Not => !in_nodes
+                .iter()
+                .any(|child_i| self.nodes[*child_i].state.as_bool()),
+            Operator::SrLatch { .. } => {
+                let set = self.in_state(i, 0);
+                let reset = self.in_state(i, 1);
+                if reset {
+                    false
+                } else if set {
+                    true
+                } else {
+                    state
                 }
-                s
             }
-            Operator::Or => {
-                let mut s = false;
-                for child_i in children {
-                    s = s || self.nodes[child_i].state;
+            Operator::DFlipFlop { .. } => {
+                let data = self.in_state(i, 0);
+                let clock = self.in_state(i, 1);
+                if self.rising_edge(i, clock) {
+                    data
+                } else {
+                    state
                 }
-                s
             }
-            Operator::Not => {
-                let mut s = false;
-                for child_i in children {
-                    s = s || self.nodes[child_i].state;
+            Operator::TFlipFlop { .. } => {
+                let toggle = self.in_state(i, 0);
+                state ^ self.rising_edge(i, toggle)
+            }
+            Operator::Counter { n, .. } => {
+                let count = self.in_state(i, 0);
+                let reset = self.in_state(i, 1);
+                if self.rising_edge(i, count) {
+                    self.nodes[i].memory.count = (self.nodes[i].memory.count + 1).min(n);
                 }
-                !s
+                if reset {
+                    self.nodes[i].memory.count = 0;
+                }
+                self.nodes[i].memory.count >= n
+            }
+            Operator::Delay { secs } => {
+                let input = self.in_state(i, 0);
+                let memory = &mut self.nodes[i].memory;
+                memory.timer = match input {
+                    true => (memory.timer + delta).min(secs),
+                    false => 0.0,
+                };
+                input && memory.timer >= secs
             }
+            Operator::Pulse { secs } => {
+                let input = self.in_state(i, 0);
+                let rising = self.rising_edge(i, input);
+                let memory = &mut self.nodes[i].memory;
+                if rising {
+                    memory.timer = secs;
+                    true
+                } else {
+                    memory.timer = (memory.timer - delta).max(0.0);
+                    memory.timer > 0.0
+                }
+            }
+            Operator::Hold { secs } => {
+                let input = self.in_state(i, 0);
+                let memory = &mut self.nodes[i].memory;
+                if input {
+                    memory.timer = secs;
+                    true
+                } else {
+                    memory.timer = (memory.timer - delta).max(0.0);
+                    memory.timer > 0.0
+                }
+            }
+            _ => state,
         };
+    }
 
-        self.nodes[i].updated = true;
+    fn in_state(&self, i: usize, in_i: usize) -> bool {
+        return self.in_signal(i, in_i).as_bool();
     }
 
-    fn reset_updated(&mut self) {
-        for node in self.nodes.iter_mut() {
-            node.updated = false;
-        }
+    fn in_value(&self, i: usize, in_i: usize) -> f32 {
+        return self.in_signal(i, in_i).as_f32();
+    }
+
+    fn in_signal(&self, i: usize, in_i: usize) -> Signal {
+        return match self.nodes[i].in_nodes.get(in_i) {
+            Some(child_i) => self.nodes[*child_i].state,
+            None => Signal::default(),
+        };
+    }
+
+    fn rising_edge(&mut self, i: usize, value: bool) -> bool {
+        let memory = &mut self.nodes[i].memory;
+        let rising = value && !memory.prev_in;
+        memory.prev_in = value;
+        return rising;
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Node {
     #[serde(skip)]
-    state: bool,
+    state: Signal,
     #[serde(skip)]
-    updated: bool,
+    memory: Memory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
     op: Operator,
+    #[serde(rename = "in_nodes")]
+    in_refs: Vec<NodeRef>,
+    #[serde(skip)]
     in_nodes: Vec<usize>,
 }
 impl Node {
     pub fn new(op: Operator, in_nodes: Vec<usize>) -> Self {
+        return Self::from_refs(None, op, in_nodes.into_iter().map(NodeRef::Index).collect());
+    }
+
+    pub fn named(id: &str, op: Operator, in_nodes: Vec<NodeRef>) -> Self {
+        return Self::from_refs(Some(id.to_string()), op, in_nodes);
+    }
+
+    fn from_refs(id: Option<String>, op: Operator, in_refs: Vec<NodeRef>) -> Self {
         return Self {
-            state: false,
-            updated: false,
+            state: op.initial_state(),
+            memory: Memory {
+                prev_in: false,
+                count: op.initial_count(),
+                timer: 0.0,
+            },
+            id,
             op,
-            in_nodes,
+            in_refs,
+            in_nodes: vec![],
+        };
+    }
+}
+
+// A node is referenced either by its index in nodes or by its id
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum NodeRef {
+    Index(usize),
+    Id(String),
+}
+impl NodeRef {
+    fn resolve(&self, ids: &HashMap<&str, usize>, num_nodes: usize) -> Option<usize> {
+        return match self {
+            NodeRef::Index(i) => (*i < num_nodes).then_some(*i),
+            NodeRef::Id(id) => ids.get(id.as_str()).copied(),
+        };
+    }
+}
+impl fmt::Display for NodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            NodeRef::Index(i) => write!(f, "{i}"),
+            NodeRef::Id(id) => write!(f, "\"{id}\""),
         };
     }
 }
 
-#[derive(Deserialize, Serialize)]
+// State carried between calls to process by the stateful operators
+#[derive(Default, Clone)]
+struct Memory {
+    prev_in: bool,
+    count: usize,
+    timer: f32,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
 pub enum Operator {
     None,
     And,
     Or,
     Not,
+    // in_nodes: [set, reset], reset wins when both are high
+    SrLatch {
+        #[serde(default)]
+        initial: bool,
+    },
+    // in_nodes: [data, clock], latches data on the rising edge of clock
+    DFlipFlop {
+        #[serde(default)]
+        initial: bool,
+    },
+    // in_nodes: [toggle], flips on the rising edge of toggle
+    TFlipFlop {
+        #[serde(default)]
+        initial: bool,
+    },
+    // in_nodes: [count, reset], high once n rising edges of count have been seen
+    Counter {
+        n: usize,
+        #[serde(default)]
+        initial: usize,
+    },
+    // in_nodes: [in], goes high once in has been high for secs
+    Delay {
+        secs: f32,
+    },
+    // in_nodes: [in], high for secs after the rising edge of in, a single process call if secs is 0
+    Pulse {
+        secs: f32,
+    },
+    // in_nodes: [in], stays high for secs after in drops
+    Hold {
+        secs: f32,
+    },
+    // in_nodes: [], always outputs value
+    Constant {
+        value: f32,
+    },
+    // in_nodes: [in, ..], adds up the values of in_nodes
+    Sum,
+    // in_nodes: [in, ..], smallest value of in_nodes
+    Min,
+    // in_nodes: [in, ..], largest value of in_nodes
+    Max,
+    // in_nodes: [a, b], high when a compared to b holds
+    Compare {
+        comparison: Comparison,
+    },
+    // in_nodes: [in], high when in is at least value
+    Threshold {
+        value: f32,
+    },
+}
+impl Operator {
+    // Minimum and maximum number of in_nodes the operator accepts
+    fn arity(&self) -> (usize, usize) {
+        return match self {
+            Operator::None => (0, 0),
+            Operator::And | Operator::Or => (1, usize::MAX),
+            Operator::Not => (1, 1),
+            Operator::SrLatch { .. } | Operator::DFlipFlop { .. } => (2, 2),
+            Operator::TFlipFlop { .. } => (1, 1),
+            Operator::Counter { .. } => (1, 2),
+            Operator::Delay { .. } | Operator::Pulse { .. } | Operator::Hold { .. } => (1, 1),
+            Operator::Constant { .. } => (0, 0),
+            Operator::Sum | Operator::Min | Operator::Max => (1, usize::MAX),
+            Operator::Compare { .. } => (2, 2),
+            Operator::Threshold { .. } => (1, 1),
+        };
+    }
+
+    fn initial_state(&self) -> Signal {
+        return match self {
+            Operator::SrLatch { initial }
+            | Operator::DFlipFlop { initial }
+            | Operator::TFlipFlop { initial } => Signal::Bool(*initial),
+            Operator::Counter { n, initial } => Signal::Bool(initial >= n),
+            Operator::Constant { value } => Signal::Analog(*value),
+            _ => Signal::default(),
+        };
+    }
+
+    fn initial_count(&self) -> usize {
+        return match self {
+            Operator::Counter { n, initial } => *initial.min(n),
+            _ => 0,
+        };
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy)]
+pub enum Comparison {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+impl Comparison {
+    fn test(&self, a: f32, b: f32) -> bool {
+        return match self {
+            Comparison::Less => a < b,
+            Comparison::LessEqual => a <= b,
+            Comparison::Greater => a > b,
+            Comparison::GreaterEqual => a >= b,
+        };
+    }
+}
+
+// Value carried along the wires of the graph. Boolean operators treat any analog value above 0
+// as high and analog operators read a high bool as 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    Bool(bool),
+    Analog(f32),
+}
+impl Signal {
+    pub fn as_bool(&self) -> bool {
+        return match self {
+            Signal::Bool(b) => *b,
+            Signal::Analog(v) => *v > 0.0,
+        };
+    }
+
+    pub fn as_f32(&self) -> f32 {
+        return match self {
+            Signal::Bool(true) => 1.0,
+            Signal::Bool(false) => 0.0,
+            Signal::Analog(v) => *v,
+        };
+    }
+}
+impl Default for Signal {
+    fn default() -> Self {
+        Signal::Bool(false)
+    }
+}
+impl From<bool> for Signal {
+    fn from(value: bool) -> Self {
+        return Signal::Bool(value);
+    }
+}
+impl From<f32> for Signal {
+    fn from(value: f32) -> Self {
+        return Signal::Analog(value);
+    }
+}
+impl PartialEq<bool> for Signal {
+    fn eq(&self, other: &bool) -> bool {
+        return self.as_bool() == *other;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LogicGraphProblem {
+    InputCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    OutputCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    DuplicateId {
+        id: String,
+    },
+    MissingInputNode {
+        slot: usize,
+        node: NodeRef,
+    },
+    MissingOutputNode {
+        slot: usize,
+        node: NodeRef,
+    },
+    InputNodeHasOperator {
+        node: usize,
+    },
+    MissingInNode {
+        node: usize,
+        in_node: NodeRef,
+    },
+    WrongArity {
+        node: usize,
+        min: usize,
+        max: usize,
+        found: usize,
+    },
+    Cycle {
+        nodes: Vec<usize>,
+    },
+}
+impl fmt::Display for LogicGraphProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            LogicGraphProblem::InputCountMismatch { expected, found } => write!(
+                f,
+                "level has {expected} unnamed input states but the graph has {found} input nodes"
+            ),
+            LogicGraphProblem::OutputCountMismatch { expected, found } => write!(
+                f,
+                "level has {expected} unnamed output states but the graph has {found} output nodes"
+            ),
+            LogicGraphProblem::DuplicateId { id } => {
+                write!(f, "more than one node has the id \"{id}\"")
+            }
+            LogicGraphProblem::MissingInputNode { slot, node } => {
+                write!(
+                    f,
+                    "input {slot} is wired to node {node} which does not exist"
+                )
+            }
+            LogicGraphProblem::MissingOutputNode { slot, node } => {
+                write!(
+                    f,
+                    "output {slot} is wired to node {node} which does not exist"
+                )
+            }
+            LogicGraphProblem::InputNodeHasOperator { node } => {
+                write!(f, "input node {node} must use the None operator")
+            }
+            LogicGraphProblem::MissingInNode { node, in_node } => {
+                write!(
+                    f,
+                    "node {node} reads from node {in_node} which does not exist"
+                )
+            }
+            LogicGraphProblem::WrongArity {
+                node,
+                min,
+                max,
+                found,
+            } => match max {
+                &usize::MAX => write!(
+                    f,
+                    "node {node} needs at least {min} in_nodes, found {found}"
+                ),
+                max if min == max => write!(f, "node {node} needs {min} in_nodes, found {found}"),
+                max => write!(
+                    f,
+                    "node {node} needs {min} to {max} in_nodes, found {found}"
+                ),
+            },
+            LogicGraphProblem::Cycle { nodes } => write!(f, "nodes {nodes:?} form a cycle"),
+        };
+    }
+}
+#[derive(Debug)]
+pub struct LogicGraphError {
+    pub problems: Vec<LogicGraphProblem>,
+}
+impl fmt::Display for LogicGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid logic graph")?;
+        for problem in self.problems.iter() {
+            write!(f, "\n  {problem}")?;
+        }
+        return Ok(());
+    }
 }
@@ -0,0 +1,264 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::consts::FIXED_TIMESTEP_HZ;
+
+use super::logic_graph::{LogicGraph, Signal};
+
+const SOLVER_TICK_SECS: f32 = (1.0 / FIXED_TIMESTEP_HZ) as f32;
+const WEIGHT_EPSILON: f32 = 0.0001;
+
+// The values one level input can be driven to
+#[derive(Clone)]
+pub struct SolverInput {
+    // The first value is the one the input rests at
+    pub values: Vec<Signal>,
+    // Something has to stay on the input to keep it away from its resting value
+    pub held: bool,
+    // False if values were dropped to stay under the cap
+    pub complete: bool,
+}
+impl SolverInput {
+    pub fn button(held: bool) -> Self {
+        return Self {
+            values: vec![Signal::Bool(false), Signal::Bool(true)],
+            held,
+            complete: true,
+        };
+    }
+
+    // Every total a group of the weights can put on the plate, built up one weight at a time and
+    // thinned out evenly whenever there are more than max_values
+    pub fn pressure_plate(weights: &[f32], max_values: usize) -> Self {
+        let max_values = max_values.max(2);
+        let mut sums = vec![0.0];
+        let mut complete = true;
+        for weight in weights.iter() {
+            let mut next = sums.clone();
+            next.extend(sums.iter().map(|sum| sum + weight));
+            next.sort_by(f32::total_cmp);
+            next.dedup_by(|a, b| (*a - *b).abs() < WEIGHT_EPSILON);
+            if next.len() > max_values {
+                next = (0..max_values)
+                    .map(|i| next[i * (next.len() - 1) / (max_values - 1)])
+                    .collect();
+                complete = false;
+            }
+            sums = next;
+        }
+        return Self {
+            values: sums.into_iter().map(Signal::Analog).collect(),
+            held: true,
+            complete,
+        };
+    }
+
+    pub fn dial(steps: usize) -> Self {
+        let steps = steps.max(1);
+        return Self {
+            values: (0..=steps)
+                .map(|step| Signal::Analog(step as f32 / steps as f32))
+                .collect(),
+            held: false,
+            complete: true,
+        };
+    }
+}
+
+pub struct SolverLimits {
+    // How many held inputs can be kept down at once, the player, their past selves and props
+    pub holders: usize,
+    pub max_phases: usize,
+    pub max_states: usize,
+}
+
+// One set of input values kept for long enough that every timer in the graph settles
+#[derive(Clone)]
+pub struct Phase {
+    pub values: Vec<Signal>,
+    pub held: usize,
+}
+impl Phase {
+    // Input values in slot order, buttons as on and off
+    pub fn describe(&self) -> String {
+        return self
+            .values
+            .iter()
+            .map(|value| match value {
+                Signal::Bool(true) => "on".to_string(),
+                Signal::Bool(false) => "off".to_string(),
+                Signal::Analog(value) => format!("{value:.2}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+}
+
+pub struct SolveReport {
+    // Shortest sequence of phases that has every output open at once
+    pub solution: Option<Vec<Phase>>,
+    // Shortest sequence of phases that opens each output in slot order. The doors on the way to
+    // the goal may never have to be open together, so each is solved on its own too
+    pub output_solutions: Vec<Option<Vec<Phase>>>,
+    // Every output is open before any input is touched
+    pub trivial: bool,
+    // Outputs in slot order that only ever showed one state, with that state. Only filled in when
+    // the search was complete, otherwise an unexplored state might still move them
+    pub stuck_outputs: Vec<(usize, bool)>,
+    // False if the search hit its limits, a missing solution may still exist
+    pub complete: bool,
+}
+impl SolveReport {
+    // Most inputs the solution has held down at the same time
+    pub fn max_held(&self) -> usize {
+        return match &self.solution {
+            Some(phases) => phases.iter().map(|phase| phase.held).max().unwrap_or(0),
+            None => 0,
+        };
+    }
+
+    // Outputs in slot order that no sequence of phases ever opened
+    pub fn never_opened(&self) -> Vec<usize> {
+        return self
+            .output_solutions
+            .iter()
+            .enumerate()
+            .filter(|(_, solution)| solution.is_none())
+            .map(|(i, _)| i)
+            .collect();
+    }
+}
+
+// Breadth first search over the graph's states, every step tries every phase the holders allow.
+// The graph must already be compiled against the level's slots
+pub fn solve(
+    logic_graph: &LogicGraph,
+    inputs: &[SolverInput],
+    limits: &SolverLimits,
+) -> SolveReport {
+    let (phases, phases_complete) = input_phases(inputs, limits);
+    let resting = Phase {
+        values: inputs.iter().map(|input| input.values[0]).collect(),
+        held: 0,
+    };
+    let ticks = ((logic_graph.longest_timer() / SOLVER_TICK_SECS).ceil() as usize).max(1) + 1;
+    let mut seen = vec![];
+
+    let mut start = logic_graph.clone();
+    start.reset();
+    let (trivial, opened) = run_phase(&mut start, &resting, ticks, &mut seen);
+    let mut solution = trivial.then(Vec::new);
+    let mut output_solutions = opened
+        .iter()
+        .map(|opened| opened.then(Vec::new))
+        .collect::<Vec<_>>();
+    let mut complete = phases_complete && inputs.iter().all(|input| input.complete);
+
+    let mut visited = HashSet::from([start.state_key()]);
+    let mut queue = VecDeque::from([(start, vec![])]);
+    while let Some((graph, path)) = queue.pop_front() {
+        if path.len() >= limits.max_phases {
+            complete = false;
+            continue;
+        }
+        for phase in phases.iter() {
+            let mut next = graph.clone();
+            let (all_opened, opened) = run_phase(&mut next, phase, ticks, &mut seen);
+            let mut next_path: Vec<Phase> = path.clone();
+            next_path.push(phase.clone());
+            if all_opened && solution.is_none() {
+                solution = Some(next_path.clone());
+            }
+            for (output_solution, opened) in output_solutions.iter_mut().zip(opened) {
+                if opened && output_solution.is_none() {
+                    *output_solution = Some(next_path.clone());
+                }
+            }
+
+            if visited.len() >= limits.max_states {
+                complete = false;
+                break;
+            }
+            if visited.insert(next.state_key()) {
+                queue.push_back((next, next_path));
+            }
+        }
+    }
+
+    let stuck_outputs = match complete {
+        true => seen
+            .iter()
+            .enumerate()
+            .filter_map(|(i, states)| match states {
+                [true, false] => Some((i, false)),
+                [false, true] => Some((i, true)),
+                _ => None,
+            })
+            .collect(),
+        false => vec![],
+    };
+
+    return SolveReport {
+        solution,
+        output_solutions,
+        trivial,
+        stuck_outputs,
+        complete,
+    };
+}
+
+// Every combination of input values that needs no more than the holders inputs held down. Stops
+// at max_states combinations and returns false if any were left out
+fn input_phases(inputs: &[SolverInput], limits: &SolverLimits) -> (Vec<Phase>, bool) {
+    let mut phases = vec![Phase {
+        values: vec![],
+        held: 0,
+    }];
+    let mut complete = true;
+    for input in inputs.iter() {
+        let mut next = vec![];
+        'phases: for phase in phases.iter() {
+            for (i, value) in input.values.iter().enumerate() {
+                let held = phase.held + (input.held && i > 0) as usize;
+                if held > limits.holders {
+                    continue;
+                }
+                if next.len() >= limits.max_states {
+                    complete = false;
+                    break 'phases;
+                }
+                let mut values = phase.values.clone();
+                values.push(*value);
+                next.push(Phase { values, held });
+            }
+        }
+        phases = next;
+    }
+    return (phases, complete);
+}
+
+// Runs the phase and records which states each output showed. Returns whether every output was
+// open at the same time and which outputs were open at any point
+fn run_phase(
+    logic_graph: &mut LogicGraph,
+    phase: &Phase,
+    ticks: usize,
+    seen: &mut Vec<[bool; 2]>,
+) -> (bool, Vec<bool>) {
+    let mut all_opened = false;
+    let mut opened = vec![];
+    for _ in 0..ticks {
+        // process hands outputs back last slot first
+        let mut outputs = logic_graph.process(phase.values.clone(), SOLVER_TICK_SECS);
+        outputs.reverse();
+        if seen.len() < outputs.len() {
+            seen.resize(outputs.len(), [false; 2]);
+        }
+        opened.resize(outputs.len(), false);
+        for (i, output) in outputs.iter().enumerate() {
+            seen[i][output.as_bool() as usize] = true;
+            opened[i] |= output.as_bool();
+        }
+        all_opened |= outputs.iter().all(|output| output.as_bool());
+    }
+    return (all_opened, opened);
+}
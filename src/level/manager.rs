@@ -1,17 +1,21 @@
 use std::collections::HashMap;
 
 use bevy::{
-    app::{Plugin, PostUpdate, Startup},
+    app::{FixedUpdate, Plugin, Startup},
     asset::Assets,
+    log::error,
     math::{vec2, Vec3},
-    prelude::{Commands, Query, Res, ResMut, Resource},
+    prelude::{in_state, Commands, IntoSystemConfigs, Query, Res, ResMut, Resource},
     sprite::ColorMaterial,
+    time::Time,
 };
+use bevy_rapier2d::plugin::PhysicsSet;
 use bevy_trait_query::One;
 
 use crate::{
     configuration::{level::LevelConfig, material::HSL, Config},
     handles::Handles,
+    ui::ui_manager::GameState,
 };
 
 use super::{
@@ -104,6 +108,7 @@ impl LevelManagerPlugin {
             goal,
             logic_graph,
             level_materials,
+            1,
         );
 
         return bp;
@@ -122,7 +127,12 @@ impl Plugin for LevelManagerPlugin {
         app.insert_resource(LevelManager { cur_level: None })
             .insert_resource(LevelMaterialHandles::default())
             .add_systems(Startup, gen_level)
-            .add_systems(PostUpdate, update_level_state);
+            .add_systems(
+                FixedUpdate,
+                update_level_state
+                    .after(PhysicsSet::Writeback)
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -130,18 +140,23 @@ fn gen_level(level_config: Res<LevelConfig>) {
     if level_config.gen_on_start {
         let blueprint = LevelManagerPlugin::gen_blueprint();
         if level_config.save_on_start {
-            blueprint.save_cfg(&format!("{}/{}", level_config.dir, level_config.cur_level));
+            let path = format!("{}/{}", level_config.dir, level_config.cur_level);
+            if let Err(err) = blueprint.save_cfg(&path) {
+                error!("{err}");
+            }
         }
     }
 }
 
-fn update_level_state(
+// Runs on the fixed tick once the inputs have read the step's contacts, the doors follow after
+pub fn update_level_state(
     mut level_manager: ResMut<LevelManager>,
     inputs: Query<One<&dyn Input>>,
     mut outputs: Query<One<&mut dyn Output>>,
+    time: Res<Time>,
 ) {
     if let Some(level) = &mut level_manager.cur_level {
-        level.update_state(&inputs, &mut outputs);
+        level.update_state(&inputs, &mut outputs, time.delta_seconds());
     }
 }
 
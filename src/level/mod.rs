@@ -1,17 +1,25 @@
-mod blueprints;
-mod bundles;
-mod goal;
-mod input;
+pub mod blueprints;
+pub mod bundles;
+pub mod goal;
+pub mod input;
 mod level;
 pub mod level_pack;
 mod logic_graph;
+pub mod logic_solver;
 pub mod manager;
 mod output;
-mod time_shift;
+pub mod pack_manifest;
+pub mod time_shift;
+pub mod time_visuals;
 
 #[cfg(test)]
 mod tests {
-    use super::logic_graph::{LogicGraph, Node, Operator};
+    use super::logic_graph::{
+        Comparison, LogicGraph, LogicGraphProblem, Node, NodeRef, Operator, Signal,
+    };
+    use super::logic_solver::{solve, SolverInput, SolverLimits};
+    use super::{level_pack::LevelPack, pack_manifest::PackManifest};
+    use serde_json::json;
 
     #[test]
     fn or_logic_graph() {
@@ -22,16 +30,16 @@ mod tests {
         ];
 
         let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
-        let out = logic_graph.process(vec![true, true]);
+        let out = logic_graph.process(vec![true, true], 0.0);
         assert!(out[0] == true);
 
-        let out = logic_graph.process(vec![true, false]);
+        let out = logic_graph.process(vec![true, false], 0.0);
         assert!(out[0] == true);
 
-        let out = logic_graph.process(vec![false, true]);
+        let out = logic_graph.process(vec![false, true], 0.0);
         assert!(out[0] == true);
 
-        let out = logic_graph.process(vec![false, false]);
+        let out = logic_graph.process(vec![false, false], 0.0);
         assert!(out[0] == false);
     }
 
@@ -44,16 +52,16 @@ mod tests {
         ];
 
         let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
-        let out = logic_graph.process(vec![true, true]);
+        let out = logic_graph.process(vec![true, true], 0.0);
         assert!(out[0] == true);
 
-        let out = logic_graph.process(vec![true, false]);
+        let out = logic_graph.process(vec![true, false], 0.0);
         assert!(out[0] == false);
 
-        let out = logic_graph.process(vec![false, true]);
+        let out = logic_graph.process(vec![false, true], 0.0);
         assert!(out[0] == false);
 
-        let out = logic_graph.process(vec![false, false]);
+        let out = logic_graph.process(vec![false, false], 0.0);
         assert!(out[0] == false);
     }
     #[test]
@@ -64,10 +72,645 @@ mod tests {
         ];
 
         let mut logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
-        let out = logic_graph.process(vec![true]);
+        let out = logic_graph.process(vec![true], 0.0);
         assert!(out[0] == false);
 
-        let out = logic_graph.process(vec![false]);
+        let out = logic_graph.process(vec![false], 0.0);
         assert!(out[0] == true);
     }
+
+    #[test]
+    fn sr_latch_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::SrLatch { initial: false }, vec![0, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
+        let out = logic_graph.process(vec![false, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false, false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![true, true], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![false, false], 0.0);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn d_flip_flop_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::DFlipFlop { initial: true }, vec![0, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
+        let out = logic_graph.process(vec![false, false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false, true], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, true], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, true], 0.0);
+        assert!(out[0] == true);
+    }
+
+    #[test]
+    fn t_flip_flop_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::TFlipFlop { initial: false }, vec![0]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn counter_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Counter { n: 2, initial: 0 }, vec![0, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
+        let out = logic_graph.process(vec![true, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![false, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false, true], 0.0);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn sequence_logic_graph() {
+        // Door only opens when A is pressed before B, C forgets A
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::SrLatch { initial: false }, vec![0, 2]),
+            Node::new(Operator::And, vec![3, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1, 2], vec![4], nodes);
+        assert!(logic_graph.validate(&[None, None, None], &[None]).is_ok());
+        let out = logic_graph.process(vec![false, true, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true, false, false], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![false, true, false], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false, false, true], 0.0);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![false, true, false], 0.0);
+        assert!(out[0] == false);
+
+        logic_graph.process(vec![true, false, false], 0.0);
+        logic_graph.reset();
+        let out = logic_graph.process(vec![false, true, false], 0.0);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn delay_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Delay { secs: 1.0 }, vec![0]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false], 0.5);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn pulse_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Pulse { secs: 0.0 }, vec![0]),
+            Node::new(Operator::Pulse { secs: 1.0 }, vec![0]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![1, 2], nodes);
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out == vec![true, true]);
+
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out == vec![false, true]);
+
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out == vec![false, false]);
+    }
+
+    #[test]
+    fn hold_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Hold { secs: 1.0 }, vec![0]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
+        let out = logic_graph.process(vec![false], 0.5);
+        assert!(out[0] == false);
+
+        let out = logic_graph.process(vec![true], 0.5);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false], 0.5);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false], 0.5);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn valid_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::And, vec![0, 1]),
+            Node::new(Operator::Not, vec![2]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0, 1], vec![2, 3], nodes);
+        assert!(logic_graph.validate(&[None, None], &[None, None]).is_ok());
+        assert!(LogicGraph::new(vec![], vec![], vec![])
+            .validate(&[None], &[None])
+            .is_ok());
+    }
+
+    #[test]
+    fn invalid_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Not, vec![0, 3]),
+            Node::new(Operator::Or, vec![1, 5]),
+            Node::new(Operator::And, vec![2]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0, 1, 7], vec![3], nodes);
+        let input_slots = [None, None, Some("a".to_string())];
+        let problems = logic_graph
+            .validate(&input_slots, &[None, None])
+            .unwrap_err()
+            .problems;
+        assert!(problems.contains(&LogicGraphProblem::InputCountMismatch {
+            expected: 2,
+            found: 3
+        }));
+        assert!(problems.contains(&LogicGraphProblem::OutputCountMismatch {
+            expected: 2,
+            found: 1
+        }));
+        assert!(problems.contains(&LogicGraphProblem::InputNodeHasOperator { node: 1 }));
+        assert!(problems.contains(&LogicGraphProblem::MissingInputNode {
+            slot: 2,
+            node: NodeRef::Id("a".to_string())
+        }));
+        assert!(problems.contains(&LogicGraphProblem::MissingInNode {
+            node: 2,
+            in_node: NodeRef::Index(5)
+        }));
+        assert!(problems.contains(&LogicGraphProblem::WrongArity {
+            node: 1,
+            min: 1,
+            max: 1,
+            found: 2
+        }));
+        assert!(problems.contains(&LogicGraphProblem::Cycle {
+            nodes: vec![1, 3, 2]
+        }));
+        assert!(problems.len() == 7);
+    }
+
+    #[test]
+    fn diamond_logic_graph() {
+        // Each layer reads the previous one twice, recursive evaluation would take 2^100 steps
+        let mut nodes = vec![Node::new(Operator::None, vec![])];
+        for i in 0..100 {
+            nodes.push(Node::new(Operator::And, vec![i, i]));
+        }
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![100], nodes);
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out[0] == true);
+
+        let out = logic_graph.process(vec![false], 0.0);
+        assert!(out[0] == false);
+    }
+
+    #[test]
+    fn out_of_order_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::Not, vec![2]),
+            Node::new(Operator::Or, vec![3, 4]),
+            Node::new(Operator::And, vec![1, 3]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![3, 4], vec![0, 2], nodes);
+        let out = logic_graph.process(vec![true, false], 0.0);
+        assert!(out == vec![false, true]);
+
+        let out = logic_graph.process(vec![false, true], 0.0);
+        assert!(out == vec![true, false]);
+    }
+
+    #[test]
+    fn indexed_logic_graph_json() {
+        let json = r#"{
+            "input_nodes": [0],
+            "output_nodes": [0, 1],
+            "nodes": [
+                { "op": "None", "in_nodes": [] },
+                { "op": "Not", "in_nodes": [0] }
+            ]
+        }"#;
+
+        let mut logic_graph: LogicGraph = serde_json::from_str(json).unwrap();
+        assert!(logic_graph.validate(&[None], &[None, None]).is_ok());
+        logic_graph.compile(&[None], &[None, None]);
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out == vec![true, false]);
+    }
+
+    #[test]
+    fn named_logic_graph_json() {
+        let json = r#"{
+            "input_nodes": [],
+            "output_nodes": [],
+            "nodes": [
+                { "id": "not_b", "op": "Not", "in_nodes": ["b"] },
+                { "id": "a", "op": "None", "in_nodes": [] },
+                { "id": "b", "op": "None", "in_nodes": [] },
+                { "id": "door", "op": "And", "in_nodes": ["a", "not_b"] }
+            ]
+        }"#;
+        let input_slots = [Some("b".to_string()), Some("a".to_string())];
+        let output_slots = [Some("not_b".to_string()), Some("door".to_string())];
+
+        let mut logic_graph: LogicGraph = serde_json::from_str(json).unwrap();
+        assert!(logic_graph.validate(&input_slots, &output_slots).is_ok());
+        logic_graph.compile(&input_slots, &output_slots);
+
+        // Outputs pop from the back so the first output slot reads the last element
+        let out = logic_graph.process(vec![false, true], 0.0);
+        assert!(out == vec![true, true]);
+
+        let out = logic_graph.process(vec![true, true], 0.0);
+        assert!(out == vec![false, false]);
+    }
+
+    #[test]
+    fn mixed_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::named("b", Operator::None, vec![]),
+            Node::named(
+                "b",
+                Operator::Or,
+                vec![NodeRef::Index(0), NodeRef::Id("c".to_string())],
+            ),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0], vec![2], nodes);
+        let input_slots = [None, Some("b".to_string())];
+        let problems = logic_graph
+            .validate(&input_slots, &[None])
+            .unwrap_err()
+            .problems;
+        assert!(problems.contains(&LogicGraphProblem::DuplicateId {
+            id: "b".to_string()
+        }));
+        assert!(problems.contains(&LogicGraphProblem::InputNodeHasOperator { node: 2 }));
+        assert!(problems.contains(&LogicGraphProblem::MissingInNode {
+            node: 2,
+            in_node: NodeRef::Id("c".to_string())
+        }));
+        assert!(problems.len() == 3);
+    }
+
+    #[test]
+    fn analog_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Sum, vec![0, 1]),
+            Node::new(Operator::Constant { value: 15.0 }, vec![]),
+            Node::new(
+                Operator::Compare {
+                    comparison: Comparison::GreaterEqual,
+                },
+                vec![2, 3],
+            ),
+            Node::new(Operator::Threshold { value: 10.0 }, vec![0]),
+            Node::new(Operator::Min, vec![0, 1]),
+            Node::new(Operator::Max, vec![0, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2, 4, 5, 6, 7], nodes);
+        let out = logic_graph.process(vec![Signal::Analog(10.0), Signal::Bool(true)], 0.0);
+        assert!(out[0] == Signal::Analog(11.0));
+        assert!(out[1] == false);
+        assert!(out[2] == true);
+        assert!(out[3] == Signal::Analog(1.0));
+        assert!(out[4] == Signal::Analog(10.0));
+
+        let out = logic_graph.process(vec![Signal::Analog(5.0), Signal::Analog(12.0)], 0.0);
+        assert!(out[0] == Signal::Analog(17.0));
+        assert!(out[1] == true);
+        assert!(out[2] == false);
+        assert!(out[3] == Signal::Analog(5.0));
+        assert!(out[4] == Signal::Analog(12.0));
+    }
+
+    #[test]
+    fn mixed_signal_logic_graph() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::And, vec![0, 1]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
+        let out = logic_graph.process(vec![Signal::Analog(0.5), Signal::Bool(true)], 0.0);
+        assert!(out[0] == Signal::Bool(true));
+
+        let out = logic_graph.process(vec![Signal::Analog(0.0), Signal::Bool(true)], 0.0);
+        assert!(out[0] == Signal::Bool(false));
+    }
+
+    #[test]
+    fn edit_logic_graph_slots() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Not, vec![0]),
+        ];
+
+        let mut logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
+        logic_graph.push_input_node();
+        logic_graph.push_output_node();
+        assert!(logic_graph.validate(&[None, None], &[None, None]).is_ok());
+
+        // The first output slot pops the last state, it still reads the Not node
+        logic_graph.compile(&[None, None], &[None, None]);
+        let out = logic_graph.process(vec![false, true], 0.0);
+        assert!(out.len() == 2);
+        assert!(out[1] == true);
+
+        logic_graph.remove_input_node(1);
+        logic_graph.remove_output_node(0);
+        assert!(logic_graph.validate(&[None], &[None]).is_ok());
+        logic_graph.compile(&[None], &[None]);
+        let out = logic_graph.process(vec![true], 0.0);
+        assert!(out == vec![Signal::Bool(false)]);
+    }
+
+    fn solver_limits(holders: usize) -> SolverLimits {
+        return SolverLimits {
+            holders,
+            max_phases: 8,
+            max_states: 1000,
+        };
+    }
+
+    #[test]
+    fn solver_opposed_doors_unsolvable() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Not, vec![0]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0], vec![0, 1], nodes);
+        let report = solve(
+            &logic_graph,
+            &[SolverInput::button(true)],
+            &solver_limits(3),
+        );
+        assert!(report.solution.is_none());
+        assert!(!report.trivial);
+        assert!(report.complete);
+        assert!(report.stuck_outputs.is_empty());
+    }
+
+    #[test]
+    fn solver_opposed_doors_open_separately() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Not, vec![0]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0], vec![0, 1], nodes);
+        let report = solve(
+            &logic_graph,
+            &[SolverInput::button(true)],
+            &solver_limits(1),
+        );
+        assert!(report.solution.is_none());
+        assert!(report.never_opened().is_empty());
+        // The first slot reads the last output node, the Not that is open at rest
+        assert!(report.output_solutions[0].as_ref().unwrap().is_empty());
+        assert!(report.output_solutions[1].as_ref().unwrap().len() == 1);
+    }
+
+    #[test]
+    fn solver_trivial() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Not, vec![0]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0], vec![1], nodes);
+        let report = solve(
+            &logic_graph,
+            &[SolverInput::button(false)],
+            &solver_limits(1),
+        );
+        assert!(report.trivial);
+        assert!(report.solution.unwrap().is_empty());
+    }
+
+    #[test]
+    fn solver_needs_holders() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::And, vec![0, 1]),
+        ];
+        let logic_graph = LogicGraph::new(vec![0, 1], vec![2], nodes);
+        let inputs = [SolverInput::button(true), SolverInput::button(true)];
+
+        let report = solve(&logic_graph, &inputs, &solver_limits(1));
+        assert!(report.solution.is_none());
+        assert!(report.complete);
+
+        let report = solve(&logic_graph, &inputs, &solver_limits(2));
+        assert!(report.solution.unwrap().len() == 1);
+        assert!(report.stuck_outputs.is_empty());
+
+        let inputs = [SolverInput::button(false), SolverInput::button(false)];
+        let report = solve(&logic_graph, &inputs, &solver_limits(1));
+        assert!(report.max_held() == 0);
+    }
+
+    #[test]
+    fn solver_toggle_sequence() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::TFlipFlop { initial: false }, vec![0]),
+            Node::new(Operator::Not, vec![0]),
+            Node::new(Operator::And, vec![1, 2]),
+        ];
+
+        let logic_graph = LogicGraph::new(vec![0], vec![3], nodes);
+        let report = solve(
+            &logic_graph,
+            &[SolverInput::button(true)],
+            &solver_limits(1),
+        );
+        let solution = report.solution.unwrap();
+        assert!(solution.len() == 2);
+        assert!(solution[0].values[0] == true);
+        assert!(solution[1].values[0] == false);
+    }
+
+    #[test]
+    fn solver_stuck_output() {
+        let nodes = vec![
+            Node::new(Operator::None, vec![]),
+            Node::new(Operator::Constant { value: 0.0 }, vec![]),
+        ];
+
+        // Outputs are reported in slot order, the first slot reads the last output node
+        let logic_graph = LogicGraph::new(vec![0], vec![0, 1], nodes);
+        let inputs = [SolverInput::dial(2)];
+        let report = solve(&logic_graph, &inputs, &solver_limits(1));
+        assert!(report.solution.is_none());
+        assert!(report.stuck_outputs == vec![(0, false)]);
+    }
+
+    #[test]
+    fn solver_pressure_plate_weights() {
+        let input = SolverInput::pressure_plate(&[1.0, 2.0, 3.0], 16);
+        assert!(input.complete);
+        assert!(input.values.len() == 7);
+        assert!(input.values[0] == Signal::Analog(0.0));
+        assert!(input.values[6] == Signal::Analog(6.0));
+
+        // Groups with the same total share one value
+        let input = SolverInput::pressure_plate(&[1.0; 10], 16);
+        assert!(input.complete);
+        assert!(input.values.len() == 11);
+
+        // 2^64 groups would never finish, the sums are capped as they're built
+        let weights = (0..64).map(|i| 1.5_f32.powi(i)).collect::<Vec<f32>>();
+        let input = SolverInput::pressure_plate(&weights, 16);
+        assert!(!input.complete);
+        assert!(input.values.len() == 16);
+        assert!(input.values[0] == Signal::Analog(0.0));
+    }
+
+    #[test]
+    fn solver_capped_search_is_incomplete() {
+        let nodes = (0..8)
+            .map(|_| Node::new(Operator::None, vec![]))
+            .chain([Node::new(Operator::And, (0..8).collect())])
+            .chain([Node::new(Operator::Constant { value: 0.0 }, vec![])])
+            .collect();
+        let logic_graph = LogicGraph::new((0..8).collect(), vec![8, 9], nodes);
+        let inputs = vec![SolverInput::dial(3); 8];
+
+        // 4^8 phases are more than the limits allow
+        let report = solve(&logic_graph, &inputs, &solver_limits(0));
+        assert!(report.solution.is_none());
+        assert!(!report.complete);
+        assert!(report.stuck_outputs.is_empty());
+    }
+
+    #[test]
+    fn empty_level_pack() {
+        let mut level_pack = LevelPack::new("empty", PackManifest::from_dirs("empty", vec![]));
+        level_pack.select_level(2);
+        assert!(level_pack.cur_level().is_none());
+        assert!(level_pack.cur_level_dir().is_none());
+        assert!(level_pack.change_level(1, |_| true).is_err());
+
+        let dirs = vec!["a".to_string(), "b".to_string()];
+        let mut level_pack = LevelPack::new("pack", PackManifest::from_dirs("pack", dirs));
+        level_pack.select_level(5);
+        assert!(level_pack.cur_level_dir().unwrap() == "pack/b");
+    }
+
+    #[test]
+    fn pack_manifest_levels() {
+        let manifest = PackManifest::from_dirs("pack", vec!["a".to_string(), "b".to_string()]);
+        assert!(manifest.title == "pack");
+        assert!(manifest.levels.len() == 2);
+        assert!(manifest.levels[0].dir == "a");
+        assert!(manifest.levels[1].dir == "b");
+        assert!(manifest
+            .levels
+            .iter()
+            .all(|level| level.requires.is_empty()));
+
+        let mut manifest: PackManifest = serde_json::from_value(json!({
+            "title": "pack",
+            "levels": [
+                { "dir": "a" },
+                { "dir": "missing", "requires": ["a"] },
+                { "dir": "b", "requires": ["a", "missing", "unknown"] },
+            ],
+        }))
+        .unwrap();
+        manifest.retain_existing(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        assert!(manifest.levels.len() == 2);
+        assert!(manifest.levels[0].dir == "a");
+        assert!(manifest.levels[1].dir == "b");
+        assert!(manifest.levels[1].requires == vec!["a".to_string()]);
+    }
 }
@@ -1,25 +1,39 @@
 use bevy::{
-    app::{Plugin, Update},
-    prelude::{Changed, Commands, Component, Entity, Query, Visibility},
+    app::{FixedUpdate, Plugin},
+    math::{Vec2, Vec3},
+    prelude::{
+        in_state, Changed, Commands, Component, Entity, IntoSystemConfigs, Query, Transform,
+        Visibility,
+    },
 };
 use bevy_rapier2d::prelude::Sensor;
 use bevy_trait_query::RegisterExt;
 use serde::{Deserialize, Serialize};
 
+use crate::ui::ui_manager::GameState;
+
+use super::{logic_graph::Signal, manager::update_level_state};
+
 pub struct OutputPlugin;
 impl Plugin for OutputPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.register_component_as::<dyn Output, Door>();
+        app.register_component_as::<dyn Output, Door>()
+            .register_component_as::<dyn Output, SlidingDoor>();
 
-        app.add_systems(Update, update_door);
+        app.add_systems(
+            FixedUpdate,
+            (update_door, update_sliding_door)
+                .after(update_level_state)
+                .run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
 #[bevy_trait_query::queryable]
 pub trait Output {
     fn get_n(&self) -> usize;
-    fn needs_state_update(&self, new_state: &mut Vec<bool>) -> bool;
-    fn pop_state(&mut self, new_state: &mut Vec<bool>);
+    fn needs_state_update(&self, new_state: &mut Vec<Signal>) -> bool;
+    fn pop_state(&mut self, new_state: &mut Vec<Signal>);
 }
 
 #[derive(Deserialize, Serialize)]
@@ -27,6 +41,13 @@ pub enum OutputType {
     Door,
 }
 
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub enum DoorType {
+    #[default]
+    Door,
+    SlidingDoor,
+}
+
 #[derive(Component)]
 pub struct Door {
     pub state: bool,
@@ -40,15 +61,15 @@ impl Output for Door {
     fn get_n(&self) -> usize {
         return 1;
     }
-    fn needs_state_update(&self, new_state: &mut Vec<bool>) -> bool {
-        let needs_update = self.state != *new_state.last().unwrap();
+    fn needs_state_update(&self, new_state: &mut Vec<Signal>) -> bool {
+        let needs_update = self.state != new_state.last().unwrap().as_bool();
         if !needs_update {
             new_state.pop();
         }
         return needs_update;
     }
-    fn pop_state(&mut self, new_state: &mut Vec<bool>) {
-        self.state = new_state.pop().unwrap();
+    fn pop_state(&mut self, new_state: &mut Vec<Signal>) {
+        self.state = new_state.pop().unwrap().as_bool();
     }
 }
 pub fn update_door(
@@ -67,3 +88,64 @@ pub fn update_door(
         }
     }
 }
+
+// Door that opens partway, retracting along its long side towards the positive end
+#[derive(Component)]
+pub struct SlidingDoor {
+    // 0 is closed and 1 is fully open
+    pub open: f32,
+    pos: Vec2,
+    shape: Vec2,
+}
+impl SlidingDoor {
+    pub fn new(pos: Vec2, shape: Vec2) -> Self {
+        return Self {
+            open: 0.0,
+            pos,
+            shape,
+        };
+    }
+}
+impl Output for SlidingDoor {
+    fn get_n(&self) -> usize {
+        return 1;
+    }
+    fn needs_state_update(&self, new_state: &mut Vec<Signal>) -> bool {
+        let open = new_state.last().unwrap().as_f32().clamp(0.0, 1.0);
+        let needs_update = (self.open - open).abs() > f32::EPSILON;
+        if !needs_update {
+            new_state.pop();
+        }
+        return needs_update;
+    }
+    fn pop_state(&mut self, new_state: &mut Vec<Signal>) {
+        self.open = new_state.pop().unwrap().as_f32().clamp(0.0, 1.0);
+    }
+}
+pub fn update_sliding_door(
+    mut commands: Commands,
+    mut doors: Query<(Entity, &SlidingDoor, &mut Transform, &mut Visibility), Changed<SlidingDoor>>,
+) {
+    for (ent, door, mut transform, mut visibility) in doors.iter_mut() {
+        if let Some(mut ent_commands) = commands.get_entity(ent) {
+            if door.open >= 1.0 {
+                ent_commands.insert(Sensor);
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            ent_commands.remove::<Sensor>();
+            *visibility = Visibility::Inherited;
+
+            let (axis, closed_len) = match door.shape.x >= door.shape.y {
+                true => (Vec3::X, door.shape.x),
+                false => (Vec3::Y, door.shape.y),
+            };
+            let len = closed_len * (1.0 - door.open);
+            let scale = door.shape.extend(1.0) * (Vec3::ONE - axis) + axis * len;
+            let offset = transform.rotation * axis * (closed_len - len) * 0.5;
+
+            transform.scale = scale;
+            transform.translation = door.pos.extend(transform.translation.z) + offset;
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::ConfigTag;
+
+// Optional pack.json at the root of a level pack
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PackManifest {
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    // Play order, levels left out of the list aren't part of the pack
+    pub levels: Vec<LevelMeta>,
+}
+impl ConfigTag for PackManifest {}
+impl PackManifest {
+    // Stand in for packs without a manifest, every level dir in name order with no unlock rules
+    pub fn from_dirs(title: &str, level_dirs: Vec<String>) -> Self {
+        return Self {
+            title: title.to_string(),
+            author: String::new(),
+            description: String::new(),
+            levels: level_dirs
+                .into_iter()
+                .map(|dir| LevelMeta {
+                    dir,
+                    name: None,
+                    par_time: None,
+                    requires: vec![],
+                })
+                .collect(),
+        };
+    }
+
+    // Drops levels whose dir is missing and unlock rules pointing at levels outside the pack
+    pub fn retain_existing(&mut self, level_dirs: &[String]) {
+        self.levels.retain(|level| {
+            let exists = level_dirs.contains(&level.dir);
+            if !exists {
+                warn!("Level {} in pack {} has no dir", level.dir, self.title);
+            }
+            return exists;
+        });
+
+        let dirs = self
+            .levels
+            .iter()
+            .map(|level| level.dir.clone())
+            .collect::<HashSet<_>>();
+        for level in self.levels.iter_mut() {
+            level.requires.retain(|required| {
+                let exists = dirs.contains(required);
+                if !exists {
+                    warn!("Level {} requires unknown level {required}", level.dir);
+                }
+                return exists;
+            });
+        }
+    }
+
+    // Dirs of levels that require each other in a loop, none of them could ever be unlocked
+    pub fn requires_cycle(&self) -> Option<Vec<String>> {
+        let requires = self
+            .levels
+            .iter()
+            .map(|level| (level.dir.as_str(), level.requires.as_slice()))
+            .collect::<HashMap<_, _>>();
+        let mut done = HashSet::new();
+        for level in self.levels.iter() {
+            let mut path = vec![];
+            if let Some(cycle) = find_cycle(&level.dir, &requires, &mut path, &mut done) {
+                return Some(cycle);
+            }
+        }
+        return None;
+    }
+
+    // Drops the unlock rules between the levels of a cycle so they can be played
+    pub fn break_cycle(&mut self, cycle: &[String]) {
+        for level in self.levels.iter_mut() {
+            if cycle.contains(&level.dir) {
+                level.requires.retain(|required| !cycle.contains(required));
+            }
+        }
+    }
+}
+
+// Depth first walk along the requires, path holds the dirs leading to dir
+fn find_cycle<'a>(
+    dir: &'a str,
+    requires: &HashMap<&'a str, &'a [String]>,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(i) = path.iter().position(|visited| *visited == dir) {
+        return Some(
+            path[i..]
+                .iter()
+                .map(|visited| visited.to_string())
+                .collect(),
+        );
+    }
+    if done.contains(dir) {
+        return None;
+    }
+
+    path.push(dir);
+    for required in requires.get(dir).copied().unwrap_or_default() {
+        if let Some(cycle) = find_cycle(required, requires, path, done) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    done.insert(dir);
+    return None;
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct LevelMeta {
+    pub dir: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    // Seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub par_time: Option<f32>,
+    // Dirs of the levels that have to be completed first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
+}
+impl LevelMeta {
+    pub fn display_name(&self) -> &str {
+        return match &self.name {
+            Some(name) => name,
+            None => &self.dir,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::PackManifest;
+
+    fn manifest(levels: serde_json::Value) -> PackManifest {
+        return serde_json::from_value(json!({ "title": "pack", "levels": levels })).unwrap();
+    }
+
+    #[test]
+    fn requires_without_cycle() {
+        let manifest = manifest(json!([
+            { "dir": "a" },
+            { "dir": "b", "requires": ["a"] },
+            { "dir": "c", "requires": ["a", "b"] },
+        ]));
+        assert!(manifest.requires_cycle().is_none());
+    }
+
+    #[test]
+    fn requires_cycle_broken() {
+        let mut manifest = manifest(json!([
+            { "dir": "a" },
+            { "dir": "b", "requires": ["a", "d"] },
+            { "dir": "c", "requires": ["b"] },
+            { "dir": "d", "requires": ["c"] },
+            { "dir": "e", "requires": ["e"] },
+        ]));
+        let cycle = manifest.requires_cycle().unwrap();
+        assert!(cycle == vec!["b".to_string(), "d".to_string(), "c".to_string()]);
+
+        manifest.break_cycle(&cycle);
+        assert!(manifest.levels[1].requires == vec!["a".to_string()]);
+        assert!(manifest.levels[2].requires.is_empty());
+
+        // Levels requiring themselves are a cycle of one
+        let cycle = manifest.requires_cycle().unwrap();
+        assert!(cycle == vec!["e".to_string()]);
+        manifest.break_cycle(&cycle);
+        assert!(manifest.requires_cycle().is_none());
+    }
+}
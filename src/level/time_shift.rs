@@ -1,75 +1,558 @@
+use std::mem;
+
 use bevy::{
-    app::{Plugin, Update},
-    input::ButtonInput,
+    app::{FixedUpdate, Plugin},
+    log::warn,
     math::Vec2,
     prelude::{
-        Component, Event, EventReader, EventWriter, KeyCode, Query, Res, ResMut, Resource,
-        Transform,
+        in_state, Commands, Component, DespawnRecursiveExt, DetectChanges, Entity, Event,
+        EventReader, EventWriter, IntoSystemConfigs, Query, Ref, Res, ResMut, Resource, Transform,
+        Visibility, Without,
     },
+    time::Time,
 };
+use bevy_rapier2d::{dynamics::Velocity, geometry::Sensor, plugin::PhysicsSet};
+use serde::{Deserialize, Serialize};
 
-use crate::configuration::key_bindings::KeyBinds;
+use crate::{consts::PARADOX_TOLERANCE, replay::PlayerInput, ui::ui_manager::GameState};
 
 pub struct TimeShiftPlugin;
 impl Plugin for TimeShiftPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.insert_resource(TimeState::default())
             .add_event::<TimeShiftEvent>()
-            .add_systems(Update, (read_time_shift_events, write_time_shift_events));
+            .add_event::<ParadoxEvent>()
+            .add_event::<RewindEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        write_time_shift_events,
+                        read_time_shift_events,
+                        update_time_presence,
+                        (play_time_shift, play_echoes),
+                        read_paradox_events,
+                    )
+                        .chain()
+                        .before(PhysicsSet::SyncBackend),
+                    record_time_shift.after(PhysicsSet::Writeback),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TimelineFrame {
+    pub pos: Vec2,
+    pub linvel: Vec2,
+}
+
+// One frame per physics tick
+#[derive(Default, Clone)]
+pub struct Timeline {
+    frames: Vec<TimelineFrame>,
+}
+impl Timeline {
+    pub fn record(&mut self, pos: Vec2, linvel: Vec2) {
+        self.frames.push(TimelineFrame { pos, linvel });
+    }
+
+    pub fn first(&self) -> Option<&TimelineFrame> {
+        return self.frames.first();
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // Drops everything after the first frame, the loop is played again from its start
+    pub fn rewind(&mut self) {
+        self.frames.truncate(1);
+    }
+}
+
+// Walks a timeline one frame per physics tick
+pub struct Playback {
+    timeline: Timeline,
+    cursor: usize,
+}
+impl Playback {
+    // Starts after the first frame, the entity is expected to be placed there on rewind
+    pub fn new(timeline: Timeline) -> Self {
+        return Self {
+            timeline,
+            cursor: 1,
+        };
+    }
+
+    // Velocity that carries an entity at pos onto the next recorded frame over delta, None once
+    // the timeline has run out. Steering by velocity rather than teleporting keeps the entity
+    // colliding with the present
+    pub fn step(&mut self, pos: Vec2, delta: f32) -> Option<Vec2> {
+        if !self.is_running() {
+            return None;
+        }
+        self.cursor += 1;
+        let frame = self.timeline.frames.get(self.cursor - 1)?;
+
+        if delta <= 0.0 {
+            return Some(frame.linvel);
+        }
+        return Some((frame.pos - pos) / delta);
+    }
+
+    // Distance between pos and the frame the last step steered towards
+    pub fn divergence(&self, pos: Vec2) -> f32 {
+        return match self.timeline.frames.get(self.cursor - 1) {
+            Some(frame) => frame.pos.distance(pos),
+            None => 0.0,
+        };
+    }
+
+    pub fn is_paradox(&self, pos: Vec2) -> bool {
+        return self.divergence(pos) > PARADOX_TOLERANCE;
+    }
+
+    // False once a step has found the timeline run out, the entity is free from then on
+    pub fn is_running(&self) -> bool {
+        return self.cursor <= self.timeline.frames.len();
+    }
+
+    pub fn first(&self) -> Option<&TimelineFrame> {
+        return self.timeline.first();
+    }
+
+    pub fn rewind(&mut self) {
+        self.cursor = 1;
+    }
+}
+
+// How an entity is treated when shifting between loops
+#[derive(Component, Deserialize, Serialize, Default, Clone, Copy, PartialEq)]
+pub enum TimeBehaviour {
+    // Stays where it was left in the present
+    Anchored,
+    // Returns to where it was left in the past and replays it in the present
+    #[default]
+    Drifting,
+    // Drifts, but only exists in the present
+    PresentOnly,
+    // Drifts, but only exists in the past
+    PastOnly,
+}
+impl TimeBehaviour {
+    pub fn exists_in(&self, time_state: &TimeState) -> bool {
+        return match self {
+            TimeBehaviour::PresentOnly => !time_state.is_past(),
+            TimeBehaviour::PastOnly => time_state.is_past(),
+            _ => true,
+        };
     }
 }
 
 #[derive(Component)]
 pub struct TimeShift {
     past_pos: Vec2,
+    recording: Timeline,
+    playback: Option<Playback>,
 }
 impl TimeShift {
     pub fn new(cur_pos: Vec2) -> Self {
-        return Self { past_pos: cur_pos };
+        let mut recording = Timeline::default();
+        recording.record(cur_pos, Vec2::ZERO);
+        return Self {
+            past_pos: cur_pos,
+            recording,
+            playback: None,
+        };
+    }
+
+    pub fn past_pos(&self) -> Vec2 {
+        return self.past_pos;
+    }
+}
+
+// Records the path of an entity that is not itself rewound, such as the player
+#[derive(Component, Default, Clone)]
+pub struct Recorder {
+    timeline: Timeline,
+}
+impl Recorder {
+    pub fn take(&mut self) -> Timeline {
+        return mem::take(&mut self.timeline);
+    }
+}
+
+// Replays a recorded path, the entity is despawned once the recording runs out
+#[derive(Component)]
+pub struct Echo {
+    playback: Playback,
+}
+impl Echo {
+    pub fn new(timeline: Timeline) -> Self {
+        return Self {
+            playback: Playback::new(timeline),
+        };
     }
 }
 
+// Loop 0 is the past, every shift after it stacks another loop on top until max_loops is reached,
+// shifting again returns to the past
 #[derive(Resource)]
 pub struct TimeState {
-    pub is_present: bool,
+    pub cur_loop: usize,
+    pub max_loops: usize,
+}
+impl TimeState {
+    pub fn new(max_loops: usize) -> Self {
+        return Self {
+            cur_loop: 0,
+            max_loops,
+        };
+    }
+
+    pub fn is_past(&self) -> bool {
+        return self.cur_loop == 0;
+    }
 }
 impl Default for TimeState {
     fn default() -> Self {
-        Self { is_present: false }
+        Self::new(1)
     }
 }
 
 #[derive(Event)]
 pub struct TimeShiftEvent;
 
+// Sent when the present stops a replayed entity from following its recorded past
+#[derive(Event)]
+pub struct ParadoxEvent {
+    pub ent: Entity,
+    pub divergence: f32,
+}
+
+// Sent once a paradox has sent everything back to where it was at the last shift
+#[derive(Event)]
+pub struct RewindEvent;
+
 fn write_time_shift_events(
     mut time_shift_ev: EventWriter<TimeShiftEvent>,
-    keys: Res<ButtonInput<KeyCode>>,
-    key_binds: Res<KeyBinds>,
+    player_input: Res<PlayerInput>,
 ) {
-    if keys.just_pressed(key_binds.time_shift.0) {
+    if player_input.time_shift {
         time_shift_ev.send(TimeShiftEvent);
     }
 }
-fn read_time_shift_events(
+pub fn read_time_shift_events(
     mut time_state: ResMut<TimeState>,
     mut time_shift_ev: EventReader<TimeShiftEvent>,
-    mut ents: Query<(&mut Transform, &mut TimeShift)>,
+    mut ents: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &mut TimeShift,
+        &TimeBehaviour,
+    )>,
 ) {
     for _ in time_shift_ev.read() {
-        match time_state.is_present {
-            true => {
-                for (mut transform, time_shift) in ents.iter_mut() {
+        if time_state.cur_loop < time_state.max_loops {
+            // Every loop replays the one before it, which already holds the loops before that
+            for (mut transform, mut velocity, mut time_shift, behaviour) in ents.iter_mut() {
+                if *behaviour == TimeBehaviour::Anchored {
+                    continue;
+                }
+                if time_state.is_past() {
+                    time_shift.past_pos = transform.translation.truncate();
+                }
+
+                let recording = mem::take(&mut time_shift.recording);
+                if let Some(first) = recording.first() {
                     let z = transform.translation.z;
-                    transform.translation = time_shift.past_pos.extend(z);
+                    transform.translation = first.pos.extend(z);
+                    velocity.linvel = first.linvel;
+                    time_shift.recording.record(first.pos, first.linvel);
                 }
+                time_shift.playback = Some(Playback::new(recording));
             }
-            false => {
-                for (transform, mut time_shift) in ents.iter_mut() {
-                    time_shift.past_pos = transform.translation.truncate();
+            time_state.cur_loop += 1;
+        } else {
+            for (mut transform, mut velocity, mut time_shift, behaviour) in ents.iter_mut() {
+                if *behaviour == TimeBehaviour::Anchored {
+                    continue;
                 }
+                let z = transform.translation.z;
+                transform.translation = time_shift.past_pos.extend(z);
+                velocity.linvel = Vec2::ZERO;
+
+                let past_pos = time_shift.past_pos;
+                time_shift.playback = None;
+                time_shift.recording.clear();
+                time_shift.recording.record(past_pos, Vec2::ZERO);
+            }
+            time_state.cur_loop = 0;
+        }
+    }
+}
+
+// Hides entities outside of the timeline they exist in and lets everything pass through them
+fn update_time_presence(
+    mut commands: Commands,
+    time_state: Res<TimeState>,
+    mut ents: Query<(Entity, Ref<TimeBehaviour>, &mut Visibility)>,
+) {
+    for (ent, behaviour, mut visibility) in ents.iter_mut() {
+        if !time_state.is_changed() && !behaviour.is_added() {
+            continue;
+        }
+        let mut ent_commands = commands.entity(ent);
+        match behaviour.exists_in(&time_state) {
+            true => {
+                ent_commands.remove::<Sensor>();
+                *visibility = Visibility::Inherited;
+            }
+            false => {
+                ent_commands.insert(Sensor);
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+// Records every time shifted entity and recorder, each loop is replayed by the next one
+fn record_time_shift(
+    mut ents: Query<(&Transform, &Velocity, &mut TimeShift, &TimeBehaviour)>,
+    mut recorders: Query<(&Transform, &Velocity, &mut Recorder)>,
+) {
+    for (transform, velocity, mut time_shift, behaviour) in ents.iter_mut() {
+        if *behaviour == TimeBehaviour::Anchored {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+        time_shift.recording.record(pos, velocity.linvel);
+    }
+    for (transform, velocity, mut recorder) in recorders.iter_mut() {
+        let pos = transform.translation.truncate();
+        recorder.timeline.record(pos, velocity.linvel);
+    }
+}
+
+// Replays the previous loop, entities are released once their recording runs out but keep it
+// around in case the loop is rewound
+fn play_time_shift(
+    time: Res<Time>,
+    time_state: Res<TimeState>,
+    mut paradox_ev: EventWriter<ParadoxEvent>,
+    mut ents: Query<(Entity, &Transform, &mut Velocity, &mut TimeShift)>,
+) {
+    for (ent, transform, mut velocity, mut time_shift) in ents.iter_mut() {
+        if let Some(playback) = &mut time_shift.playback {
+            let pos = transform.translation.truncate();
+            if !time_state.is_past() && playback.is_paradox(pos) {
+                paradox_ev.send(ParadoxEvent {
+                    ent,
+                    divergence: playback.divergence(pos),
+                });
+            }
+            if let Some(linvel) = playback.step(pos, time.delta_seconds()) {
+                velocity.linvel = linvel;
             }
         }
-        time_state.is_present = !time_state.is_present;
+    }
+}
+
+fn play_echoes(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_state: Res<TimeState>,
+    mut paradox_ev: EventWriter<ParadoxEvent>,
+    mut echoes: Query<(Entity, &Transform, &mut Velocity, &mut Echo)>,
+) {
+    for (ent, transform, mut velocity, mut echo) in echoes.iter_mut() {
+        let pos = transform.translation.truncate();
+        if !time_state.is_past() && echo.playback.is_paradox(pos) {
+            paradox_ev.send(ParadoxEvent {
+                ent,
+                divergence: echo.playback.divergence(pos),
+            });
+        }
+        match echo.playback.step(pos, time.delta_seconds()) {
+            Some(linvel) => velocity.linvel = linvel,
+            None => commands.entity(ent).despawn_recursive(),
+        }
+    }
+}
+
+// A paradox rewinds the current loop to the last shift, the previous loop replays again and the
+// player returns to where they shifted from. The attempt and its stopwatch carry on
+pub fn read_paradox_events(
+    mut paradox_ev: EventReader<ParadoxEvent>,
+    mut rewind_ev: EventWriter<RewindEvent>,
+    mut ents: Query<(&mut Transform, &mut Velocity, &mut TimeShift)>,
+    mut recorders: Query<(&mut Transform, &mut Velocity, &mut Recorder), Without<TimeShift>>,
+) {
+    let paradox = match paradox_ev.read().last() {
+        Some(paradox) => paradox,
+        None => return,
+    };
+    warn!(
+        "Paradox, {:?} strayed {} from its past, rewinding to the last shift",
+        paradox.ent, paradox.divergence
+    );
+
+    for (mut transform, mut velocity, mut time_shift) in ents.iter_mut() {
+        let time_shift = time_shift.as_mut();
+        if let Some(playback) = &mut time_shift.playback {
+            playback.rewind();
+            if let Some(first) = playback.first() {
+                let z = transform.translation.z;
+                transform.translation = first.pos.extend(z);
+                velocity.linvel = first.linvel;
+            }
+            time_shift.recording.rewind();
+        }
+    }
+    for (mut transform, mut velocity, mut recorder) in recorders.iter_mut() {
+        if let Some(first) = recorder.timeline.first() {
+            let z = transform.translation.z;
+            transform.translation = first.pos.extend(z);
+            velocity.linvel = first.linvel;
+        }
+        recorder.timeline.rewind();
+    }
+    rewind_ev.send(RewindEvent);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::{App, Update},
+        math::{vec2, Vec2},
+        prelude::Transform,
+    };
+    use bevy_rapier2d::dynamics::Velocity;
+
+    use super::{
+        read_time_shift_events, Playback, TimeBehaviour, TimeShift, TimeShiftEvent, TimeState,
+        Timeline,
+    };
+    use crate::consts::PARADOX_TOLERANCE;
+
+    fn timeline(points: &[Vec2]) -> Timeline {
+        let mut timeline = Timeline::default();
+        for point in points {
+            timeline.record(*point, Vec2::ZERO);
+        }
+        return timeline;
+    }
+
+    #[test]
+    fn playback_steers_onto_timeline() {
+        let mut playback =
+            Playback::new(timeline(&[vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(3.0, 0.0)]));
+        assert!(playback.step(vec2(0.0, 0.0), 0.5) == Some(vec2(2.0, 0.0)));
+        assert!(playback.divergence(vec2(1.0, 0.0)) == 0.0);
+        assert!(playback.step(vec2(1.0, 0.0), 0.5) == Some(vec2(4.0, 0.0)));
+        assert!(playback.divergence(vec2(3.0, 4.0)) == 4.0);
+
+        // Running out releases the entity, it can't diverge from a past it no longer has
+        assert!(playback.step(vec2(3.0, 0.0), 0.5).is_none());
+        assert!(!playback.is_running());
+        assert!(playback.divergence(vec2(100.0, 0.0)) == 0.0);
+
+        playback.rewind();
+        assert!(playback.is_running());
+        assert!(playback.step(vec2(0.0, 0.0), 0.5) == Some(vec2(2.0, 0.0)));
+    }
+
+    #[test]
+    fn paradox_tolerance() {
+        let mut playback = Playback::new(timeline(&[vec2(0.0, 0.0), vec2(0.0, 0.0)]));
+        playback.step(Vec2::ZERO, 1.0);
+        assert!(!playback.is_paradox(vec2(PARADOX_TOLERANCE, 0.0)));
+        assert!(playback.is_paradox(vec2(PARADOX_TOLERANCE + 0.1, 0.0)));
+    }
+
+    #[test]
+    fn time_behaviour_presence() {
+        let past = TimeState::new(2);
+        let mut present = TimeState::new(2);
+        present.cur_loop = 1;
+
+        assert!(TimeBehaviour::Drifting.exists_in(&past));
+        assert!(TimeBehaviour::Drifting.exists_in(&present));
+        assert!(TimeBehaviour::Anchored.exists_in(&present));
+        assert!(!TimeBehaviour::PresentOnly.exists_in(&past));
+        assert!(TimeBehaviour::PresentOnly.exists_in(&present));
+        assert!(TimeBehaviour::PastOnly.exists_in(&past));
+        assert!(!TimeBehaviour::PastOnly.exists_in(&present));
+    }
+
+    #[test]
+    fn time_shift_stacks_loops() {
+        let mut app = App::new();
+        app.insert_resource(TimeState::new(2))
+            .add_event::<TimeShiftEvent>()
+            .add_systems(Update, read_time_shift_events);
+
+        let spawn = |app: &mut App, behaviour: TimeBehaviour| {
+            return app
+                .world_mut()
+                .spawn((
+                    Transform::default(),
+                    Velocity::default(),
+                    TimeShift::new(Vec2::ZERO),
+                    behaviour,
+                ))
+                .id();
+        };
+        let drifting = spawn(&mut app, TimeBehaviour::Drifting);
+        let anchored = spawn(&mut app, TimeBehaviour::Anchored);
+        let shift = |app: &mut App, pos: Vec2| {
+            for ent in [drifting, anchored] {
+                let mut ent = app.world_mut().entity_mut(ent);
+                ent.get_mut::<Transform>().unwrap().translation = pos.extend(0.0);
+                ent.get_mut::<TimeShift>()
+                    .unwrap()
+                    .recording
+                    .record(pos, Vec2::ZERO);
+            }
+            app.world_mut().send_event(TimeShiftEvent);
+            app.update();
+        };
+        let pos = |app: &App, ent| {
+            return app
+                .world()
+                .get::<Transform>(ent)
+                .unwrap()
+                .translation
+                .truncate();
+        };
+        let playback_len = |app: &App| {
+            let time_shift = app.world().get::<TimeShift>(drifting).unwrap();
+            return time_shift
+                .playback
+                .as_ref()
+                .map(|p| p.timeline.frames.len());
+        };
+
+        // Each shift returns to the start and replays the loop before it, which holds the ones
+        // before that
+        shift(&mut app, vec2(2.0, 0.0));
+        assert!(app.world().resource::<TimeState>().cur_loop == 1);
+        assert!(pos(&app, drifting) == Vec2::ZERO);
+        assert!(playback_len(&app) == Some(2));
+
+        shift(&mut app, vec2(5.0, 0.0));
+        assert!(app.world().resource::<TimeState>().cur_loop == 2);
+        assert!(pos(&app, drifting) == Vec2::ZERO);
+        assert!(playback_len(&app) == Some(2));
+
+        // Shifting past max_loops returns to where the past was left
+        shift(&mut app, vec2(7.0, 0.0));
+        assert!(app.world().resource::<TimeState>().is_past());
+        assert!(pos(&app, drifting) == vec2(2.0, 0.0));
+        assert!(playback_len(&app).is_none());
+
+        assert!(pos(&app, anchored) == vec2(7.0, 0.0));
     }
 }
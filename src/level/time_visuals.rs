@@ -0,0 +1,131 @@
+use bevy::{
+    app::{Plugin, Startup, Update},
+    asset::Assets,
+    color::Alpha,
+    math::EulerRot,
+    prelude::{
+        default, BackgroundColor, Bundle, Commands, Component, DetectChanges, EventReader, Gizmos,
+        NodeBundle, Query, Res, ResMut, Transform,
+    },
+    sprite::ColorMaterial,
+    time::{Time, Timer, TimerMode},
+    ui::{PositionType, Style, Val, ZIndex},
+};
+
+use crate::consts::{PAST_OUTLINE_COLOR, SHIFT_FLASH_COLOR, SHIFT_FLASH_SECS};
+
+use super::{
+    blueprints::level::LevelMaterialHandles,
+    time_shift::{TimeBehaviour, TimeShift, TimeShiftEvent, TimeState},
+};
+
+pub struct TimeVisualsPlugin;
+impl Plugin for TimeVisualsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Startup, spawn_shift_flash).add_systems(
+            Update,
+            (
+                update_palette,
+                draw_past_outlines,
+                start_shift_flash,
+                update_shift_flash,
+            ),
+        );
+    }
+}
+
+// Full screen overlay that briefly lights up on every shift
+#[derive(Component)]
+pub struct ShiftFlash {
+    timer: Timer,
+}
+
+#[derive(Bundle)]
+pub struct ShiftFlashBundle {
+    node_bundle: NodeBundle,
+    shift_flash: ShiftFlash,
+}
+impl ShiftFlashBundle {
+    pub fn new(secs: f32) -> Self {
+        let mut timer = Timer::from_seconds(secs, TimerMode::Once);
+        timer.set_elapsed(timer.duration());
+
+        return Self {
+            node_bundle: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(SHIFT_FLASH_COLOR.with_alpha(0.0)),
+                z_index: ZIndex::Global(i32::MAX),
+                ..default()
+            },
+            shift_flash: ShiftFlash { timer },
+        };
+    }
+}
+
+fn spawn_shift_flash(mut commands: Commands) {
+    commands.spawn(ShiftFlashBundle::new(SHIFT_FLASH_SECS));
+}
+
+fn update_palette(
+    time_state: Res<TimeState>,
+    level_material_handles: Res<LevelMaterialHandles>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if time_state.is_changed() || level_material_handles.is_changed() {
+        level_material_handles.apply_palette(time_state.is_past(), &mut materials);
+    }
+}
+
+// Outlines where time shifted props will snap back to when returning to the past
+fn draw_past_outlines(
+    mut gizmos: Gizmos,
+    time_state: Res<TimeState>,
+    props: Query<(&Transform, &TimeShift, &TimeBehaviour)>,
+) {
+    if time_state.is_past() {
+        return;
+    }
+    for (transform, time_shift, behaviour) in props.iter() {
+        if matches!(
+            behaviour,
+            TimeBehaviour::Anchored | TimeBehaviour::PresentOnly
+        ) {
+            continue;
+        }
+        let (_, _, z_rot) = transform.rotation.to_euler(EulerRot::XYZ);
+        gizmos.rect_2d(
+            time_shift.past_pos(),
+            z_rot,
+            transform.scale.truncate(),
+            PAST_OUTLINE_COLOR,
+        );
+    }
+}
+
+fn start_shift_flash(
+    mut time_shift_ev: EventReader<TimeShiftEvent>,
+    mut flashes: Query<&mut ShiftFlash>,
+) {
+    if time_shift_ev.read().count() == 0 {
+        return;
+    }
+    for mut flash in flashes.iter_mut() {
+        flash.timer.reset();
+    }
+}
+
+fn update_shift_flash(
+    time: Res<Time>,
+    mut flashes: Query<(&mut ShiftFlash, &mut BackgroundColor)>,
+) {
+    for (mut flash, mut background_color) in flashes.iter_mut() {
+        flash.timer.tick(time.delta());
+        let alpha = SHIFT_FLASH_COLOR.alpha() * flash.timer.fraction_remaining();
+        background_color.0 = SHIFT_FLASH_COLOR.with_alpha(alpha);
+    }
+}
@@ -1,28 +1,59 @@
 use crate::player::player_bundle::PlayerPlugin;
-use bevy::app::{App, Startup};
-use bevy::prelude::{default, BuildChildren, Commands, ImagePlugin, NodeBundle, PluginGroup};
-use bevy::transform::commands;
-use bevy::ui::{JustifyContent, Style, Val};
+use bevy::app::App;
+use bevy::prelude::{default, ImagePlugin, PluginGroup};
+use bevy::time::{Fixed, Time};
 use bevy::window::{Window, WindowMode, WindowPlugin, WindowPosition};
 use bevy::{log::LogPlugin, DefaultPlugins};
 use bevy_rapier2d::prelude::*;
 use configuration::display::DisplayConfig;
 use configuration::{Config, ConfigPlugin};
-use consts::DISPLAY_CFG_PATH;
+use consts::{DISPLAY_CFG_PATH, FIXED_TIMESTEP_HZ, PIXELS_PER_METER};
+use editor::EditorPlugin;
 use handles::HandlesPlugin;
+use headless::run_headless;
+use hot_reload::HotReloadPlugin;
 use level::manager::LevelManagerPlugin;
-use ui::selection_list::{LevelPackItem, ListItem, UIListBundle};
+use level::time_visuals::TimeVisualsPlugin;
+use progress::ProgressPlugin;
+use replay::{fixed_rapier_config, ReplayPlugin};
+use solver::run_solver;
+use std::{env, process};
 use ui::ui_plugin::UIPlugin;
 
 mod configuration;
 mod consts;
+mod editor;
 mod handles;
+mod headless;
+mod hot_reload;
 mod level;
 mod player;
+mod progress;
+mod replay;
+mod solver;
 mod ui;
 
 fn main() {
-    let display_config = DisplayConfig::load_cfg(DISPLAY_CFG_PATH);
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--headless") {
+        if !run_headless(&args[i + 1..]) {
+            process::exit(1);
+        }
+        return;
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--solve") {
+        if !run_solver(&args[i + 1..]) {
+            process::exit(1);
+        }
+        return;
+    }
+    let replay_path = args
+        .iter()
+        .skip_while(|arg| *arg != "--replay")
+        .nth(1)
+        .cloned();
+
+    let display_config = DisplayConfig::load_or_default(DISPLAY_CFG_PATH);
     let window = Window {
         title: "time_trial".into(),
         resolution: (display_config.resolution.x, display_config.resolution.y).into(),
@@ -34,7 +65,8 @@ fn main() {
     };
 
     App::new()
-        .insert_resource(RapierConfiguration::new(0.0))
+        .insert_resource(fixed_rapier_config())
+        .insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
@@ -51,45 +83,17 @@ fn main() {
         )
         .add_plugins((
             ConfigPlugin,
+            EditorPlugin,
             HandlesPlugin,
+            HotReloadPlugin,
             LevelManagerPlugin,
             PlayerPlugin,
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+            ProgressPlugin,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER)
+                .in_fixed_schedule(),
+            ReplayPlugin::new(replay_path),
+            TimeVisualsPlugin,
             UIPlugin,
         ))
-        .add_systems(Startup, testing)
         .run();
 }
-
-fn testing(mut commands: Commands) {
-    let mut list_items: Vec<Box<dyn ListItem>> = vec![];
-    for i in 0..30 {
-        list_items.push(Box::new(LevelPackItem::new_item(
-            &format!("level pack name {i}"),
-            25.0,
-            98.9,
-            "Load level",
-        )));
-    }
-    commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::SpaceBetween,
-                ..default()
-            },
-            ..default()
-        })
-        .with_children(|child_builder| {
-            UIListBundle::spawn(
-                child_builder,
-                50.0,
-                100.0,
-                50.0,
-                0.0,
-                Box::new(LevelPackItem::new_title()),
-                list_items,
-            );
-        });
-}
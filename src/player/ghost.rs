@@ -0,0 +1,124 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    configuration::player::PlayerConfig,
+    consts::PLAYER_Z_OFFSET,
+    handles::Handles,
+    level::time_shift::{read_paradox_events, Echo, Recorder, RewindEvent, TimeState, Timeline},
+};
+
+use super::player_bundle::Player;
+
+#[derive(Component)]
+pub struct Ghost;
+
+// One recording per finished loop, oldest first
+#[derive(Resource, Default)]
+pub struct PastSelves(pub Vec<Timeline>);
+
+// The player's past self, replays the path the player took before shifting to the present
+#[derive(Bundle)]
+pub struct GhostBundle {
+    pub material_mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
+    pub collider: Collider,
+    pub rigid_body: RigidBody,
+    pub damping: Damping,
+    pub locked_axis: LockedAxes,
+    pub velocity: Velocity,
+    pub read_mass_properties: ReadMassProperties,
+    pub echo: Echo,
+    pub ghost: Ghost,
+}
+impl GhostBundle {
+    pub fn new(
+        material: &Handle<ColorMaterial>,
+        mesh: &Mesh2dHandle,
+        collider_radius: f32,
+        linear_damping: f32,
+        angular_damping: f32,
+        pos: Vec2,
+        timeline: Timeline,
+    ) -> Self {
+        return Self {
+            material_mesh_bundle: MaterialMesh2dBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(pos.extend(PLAYER_Z_OFFSET)),
+                ..default()
+            },
+            collider: Collider::ball(collider_radius),
+            rigid_body: RigidBody::Dynamic,
+            damping: Damping {
+                linear_damping,
+                angular_damping,
+            },
+            locked_axis: LockedAxes::ROTATION_LOCKED,
+            velocity: Velocity::default(),
+            read_mass_properties: ReadMassProperties::default(),
+            echo: Echo::new(timeline),
+            ghost: Ghost,
+        };
+    }
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        return commands.spawn(self).id();
+    }
+}
+
+pub struct GhostPlugin;
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<PastSelves>()
+            .add_systems(FixedUpdate, update_ghosts.after(read_paradox_events));
+    }
+}
+
+// Every shift restarts all ghosts together with one more for the loop that just ended, returning
+// to the past discards them all and starts a fresh recording. A rewind restarts them as they were
+fn update_ghosts(
+    mut commands: Commands,
+    time_state: Res<TimeState>,
+    mut rewind_ev: EventReader<RewindEvent>,
+    mut past_selves: ResMut<PastSelves>,
+    mut players: Query<&mut Recorder, With<Player>>,
+    ghosts: Query<Entity, With<Ghost>>,
+    player_config: Res<PlayerConfig>,
+    handles: Res<Handles>,
+) {
+    let rewound = rewind_ev.read().count() > 0;
+    if !time_state.is_changed() && !rewound {
+        return;
+    }
+    for ghost in ghosts.iter() {
+        commands.entity(ghost).despawn_recursive();
+    }
+
+    if let Ok(mut recorder) = players.get_single_mut() {
+        if time_state.is_changed() {
+            let timeline = recorder.take();
+            if time_state.is_past() {
+                past_selves.0.clear();
+                return;
+            }
+            past_selves.0.push(timeline);
+        }
+
+        for timeline in past_selves.0.iter() {
+            if let Some(first) = timeline.first() {
+                GhostBundle::new(
+                    &handles.ghost_material,
+                    &handles.player_mesh,
+                    player_config.collider_radius,
+                    player_config.linear_damping,
+                    player_config.angular_damping,
+                    first.pos,
+                    timeline.clone(),
+                )
+                .spawn(&mut commands);
+            }
+        }
+    }
+}
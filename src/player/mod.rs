@@ -1,2 +1,3 @@
+pub mod ghost;
 pub mod player_bundle;
 pub mod player_camera;
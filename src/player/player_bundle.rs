@@ -6,12 +6,11 @@ use bevy::{
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    configuration::{key_bindings::KeyBinds, player::PlayerConfig},
-    consts::PLAYER_Z_OFFSET,
-    handles::Handles,
+    configuration::player::PlayerConfig, consts::PLAYER_Z_OFFSET, handles::Handles,
+    level::time_shift::Recorder, replay::PlayerInput, ui::ui_manager::GameState,
 };
 
-use super::player_camera::PlayerCamBundle;
+use super::{ghost::GhostPlugin, player_camera::PlayerCamBundle};
 
 #[derive(Component, Clone)]
 pub struct Player;
@@ -24,6 +23,8 @@ pub struct PlayerBundle {
     pub damping: Damping,
     pub locked_axis: LockedAxes,
     pub velocity: Velocity,
+    pub read_mass_properties: ReadMassProperties,
+    pub recorder: Recorder,
     pub player: Player,
 }
 impl PlayerBundle {
@@ -50,6 +51,8 @@ impl PlayerBundle {
             },
             locked_axis: LockedAxes::ROTATION_LOCKED,
             velocity: Velocity::default(),
+            read_mass_properties: ReadMassProperties::default(),
+            recorder: Recorder::default(),
             player: Player,
         };
     }
@@ -61,8 +64,14 @@ impl PlayerBundle {
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Startup, spawn_player)
-            .add_systems(Update, move_player);
+        app.add_plugins(GhostPlugin)
+            .add_systems(Startup, spawn_player)
+            .add_systems(
+                FixedUpdate,
+                move_player
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 fn spawn_player(mut commands: Commands, player_config: Res<PlayerConfig>, handles: Res<Handles>) {
@@ -81,24 +90,23 @@ fn spawn_player(mut commands: Commands, player_config: Res<PlayerConfig>, handle
 
 fn move_player(
     mut players: Query<&mut Velocity, With<Player>>,
-    keys: Res<ButtonInput<KeyCode>>,
-    key_binds: Res<KeyBinds>,
+    player_input: Res<PlayerInput>,
     player_config: Res<PlayerConfig>,
 ) {
     if let Ok(mut player) = players.get_single_mut() {
         let mut vel = player.linvel;
         let move_speed = player_config.move_speed;
 
-        if keys.pressed(key_binds.up.0) {
+        if player_input.up {
             vel.y += move_speed;
         }
-        if keys.pressed(key_binds.left.0) {
+        if player_input.left {
             vel.x -= move_speed;
         }
-        if keys.pressed(key_binds.down.0) {
+        if player_input.down {
             vel.y -= move_speed;
         }
-        if keys.pressed(key_binds.right.0) {
+        if player_input.right {
             vel.x += move_speed;
         }
 
@@ -0,0 +1,230 @@
+use std::{collections::HashMap, env, fs, path::PathBuf};
+
+use bevy::{
+    app::{FixedPostUpdate, Plugin, Update},
+    log::error,
+    prelude::{in_state, EventReader, IntoSystemConfigs, Res, ResMut, Resource},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    configuration::{Config, ConfigTag},
+    consts::{PROGRESS_FILE, SAVE_DIR_NAME},
+    level::{
+        goal::LevelCompleteEvent, level_pack::LevelStartedEvent, pack_manifest::LevelMeta,
+        time_shift::TimeShiftEvent,
+    },
+    replay::{update_replay, ReplayState},
+    ui::ui_manager::GameState,
+};
+
+pub struct ProgressPlugin;
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.insert_resource(Progress::load())
+            .insert_resource(CurrentRun::default())
+            .add_systems(Update, start_run)
+            .add_systems(
+                FixedPostUpdate,
+                update_run
+                    .after(update_replay)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// Per user data dir following each platform's convention, falls back to the working dir
+pub fn user_data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    return base.unwrap_or(PathBuf::from(".")).join(SAVE_DIR_NAME);
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct LevelProgress {
+    pub completed: bool,
+    // Seconds
+    pub best_time: Option<f32>,
+    pub fewest_time_shifts: Option<usize>,
+    pub attempts: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PackProgress {
+    pub levels: HashMap<String, LevelProgress>,
+}
+
+// Keyed by pack dir then level dir
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct Progress {
+    packs: HashMap<String, PackProgress>,
+}
+impl ConfigTag for Progress {}
+impl Progress {
+    pub fn path() -> String {
+        return user_data_dir()
+            .join(PROGRESS_FILE)
+            .to_string_lossy()
+            .to_string();
+    }
+
+    pub fn load() -> Self {
+        if let Err(err) = fs::create_dir_all(user_data_dir()) {
+            error!("Error creating save dir, {err}");
+        }
+        return Self::load_or_default(&Self::path());
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = self.save_cfg(&Self::path()) {
+            error!("{err}");
+        }
+    }
+
+    pub fn level(&self, pack: &str, level: &str) -> Option<&LevelProgress> {
+        return self.packs.get(pack)?.levels.get(level);
+    }
+
+    pub fn is_complete(&self, pack: &str, level: &str) -> bool {
+        return match self.level(pack, level) {
+            Some(progress) => progress.completed,
+            None => false,
+        };
+    }
+
+    pub fn pack_percent(&self, pack: &str, levels: &[LevelMeta]) -> f32 {
+        if levels.is_empty() {
+            return 0.0;
+        }
+        let completed = levels
+            .iter()
+            .filter(|level| self.is_complete(pack, &level.dir))
+            .count();
+        return completed as f32 / levels.len() as f32 * 100.0;
+    }
+
+    pub fn record_attempt(&mut self, pack: &str, level: &str) {
+        self.level_mut(pack, level).attempts += 1;
+    }
+
+    pub fn record_completion(&mut self, pack: &str, level: &str, secs: f32, time_shifts: usize) {
+        let progress = self.level_mut(pack, level);
+        progress.completed = true;
+        if progress.best_time.map_or(true, |best| secs < best) {
+            progress.best_time = Some(secs);
+        }
+        if progress
+            .fewest_time_shifts
+            .map_or(true, |fewest| time_shifts < fewest)
+        {
+            progress.fewest_time_shifts = Some(time_shifts);
+        }
+    }
+
+    fn level_mut(&mut self, pack: &str, level: &str) -> &mut LevelProgress {
+        return self
+            .packs
+            .entry(pack.to_string())
+            .or_default()
+            .levels
+            .entry(level.to_string())
+            .or_default();
+    }
+}
+
+pub struct LevelRun {
+    pub pack: String,
+    pub level: String,
+    pub time_shifts: usize,
+}
+
+// The attempt in progress, None until a level has been started
+#[derive(Resource, Default)]
+pub struct CurrentRun(pub Option<LevelRun>);
+
+// Runs on the fixed tick so a completion is timed on the tick the goal was reached, before the
+// next level can start a new run
+fn update_run(
+    mut progress: ResMut<Progress>,
+    mut current_run: ResMut<CurrentRun>,
+    mut level_complete_ev: EventReader<LevelCompleteEvent>,
+    mut time_shift_ev: EventReader<TimeShiftEvent>,
+    replay_state: Res<ReplayState>,
+) {
+    let time_shifts = time_shift_ev.read().count();
+    if let Some(run) = &mut current_run.0 {
+        run.time_shifts += time_shifts;
+    }
+
+    if level_complete_ev.read().count() == 0 {
+        return;
+    }
+    if let Some(run) = current_run.0.take() {
+        progress.record_completion(
+            &run.pack,
+            &run.level,
+            replay_state.elapsed_secs(),
+            run.time_shifts,
+        );
+        progress.save();
+    }
+}
+
+fn start_run(
+    mut progress: ResMut<Progress>,
+    mut current_run: ResMut<CurrentRun>,
+    mut level_started_ev: EventReader<LevelStartedEvent>,
+) {
+    if let Some(ev) = level_started_ev.read().last() {
+        progress.record_attempt(&ev.pack, &ev.level);
+        progress.save();
+        current_run.0 = Some(LevelRun {
+            pack: ev.pack.clone(),
+            level: ev.level.clone(),
+            time_shifts: 0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    use crate::level::pack_manifest::PackManifest;
+
+    #[test]
+    fn progress_records_runs() {
+        let mut progress = Progress::default();
+        assert!(progress.level("pack", "a").is_none());
+
+        progress.record_attempt("pack", "a");
+        progress.record_attempt("pack", "a");
+        assert!(!progress.is_complete("pack", "a"));
+        assert!(progress.level("pack", "a").unwrap().attempts == 2);
+
+        progress.record_completion("pack", "a", 10.0, 3);
+        progress.record_completion("pack", "a", 12.0, 1);
+        let level = progress.level("pack", "a").unwrap();
+        assert!(level.completed);
+        assert!(level.best_time == Some(10.0));
+        assert!(level.fewest_time_shifts == Some(1));
+
+        // The best time and fewest time shifts can come from different runs
+        progress.record_completion("pack", "a", 8.0, 2);
+        let level = progress.level("pack", "a").unwrap();
+        assert!(level.best_time == Some(8.0));
+        assert!(level.fewest_time_shifts == Some(1));
+        assert!(level.attempts == 2);
+
+        let manifest = PackManifest::from_dirs("pack", vec!["a".to_string(), "b".to_string()]);
+        assert!(progress.pack_percent("pack", &manifest.levels) == 50.0);
+        assert!(progress.pack_percent("other", &manifest.levels) == 0.0);
+        assert!(!progress.is_complete("other", "a"));
+    }
+}
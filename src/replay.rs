@@ -0,0 +1,393 @@
+use std::{fs, mem};
+
+use bevy::{
+    app::{FixedPostUpdate, FixedPreUpdate, Plugin, PostStartup, PreUpdate, Update},
+    input::{ButtonInput, InputSystem},
+    log::{error, info},
+    prelude::{
+        in_state, EventReader, EventWriter, IntoSystemConfigs, KeyCode, NextState, OnExit, Res,
+        ResMut, Resource,
+    },
+};
+use bevy_rapier2d::plugin::{RapierConfiguration, TimestepMode};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    configuration::{key_bindings::KeyBinds, Config, ConfigTag},
+    consts::{FIXED_TIMESTEP_HZ, REPLAY_DIR_NAME},
+    level::{
+        goal::LevelCompleteEvent,
+        level_pack::{
+            read_change_level_event, ChangeLevelEvent, LevelPack, LevelPackRegistry,
+            LevelStartedEvent,
+        },
+        time_shift::TimeShiftEvent,
+    },
+    progress::user_data_dir,
+    ui::{error_display::ErrorEvent, ui_manager::GameState},
+};
+
+pub struct ReplayPlugin {
+    replay_path: Option<String>,
+}
+impl ReplayPlugin {
+    pub fn new(replay_path: Option<String>) -> Self {
+        return Self { replay_path };
+    }
+}
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.insert_resource(PlayerInput::default())
+            .insert_resource(InputLatch::default())
+            .insert_resource(ReplayState::new(self.replay_path.clone()))
+            .add_systems(PostStartup, start_playback)
+            .add_systems(
+                PreUpdate,
+                latch_input
+                    .after(InputSystem)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), clear_input_latch)
+            .add_systems(Update, start_replay.after(read_change_level_event))
+            .add_systems(
+                FixedPreUpdate,
+                gather_player_input.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                update_replay.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// Physics steps on the fixed timestep so replays reproduce runs exactly
+pub fn fixed_rapier_config() -> RapierConfiguration {
+    let mut rapier_config = RapierConfiguration::new(0.0);
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: (1.0 / FIXED_TIMESTEP_HZ) as f32,
+        substeps: 1,
+    };
+    return rapier_config;
+}
+
+// The player's input for one fixed tick, every gameplay system reads this instead of the keyboard
+#[derive(Resource, Default, Clone, Copy)]
+pub struct PlayerInput {
+    pub up: bool,
+    pub left: bool,
+    pub down: bool,
+    pub right: bool,
+    pub time_shift: bool,
+}
+impl PlayerInput {
+    pub fn to_bits(self) -> u8 {
+        let flags = [self.up, self.left, self.down, self.right, self.time_shift];
+        return flags
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, flag)| bits | ((*flag as u8) << i));
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        let flag = |i: u8| bits & (1 << i) != 0;
+        return Self {
+            up: flag(0),
+            left: flag(1),
+            down: flag(2),
+            right: flag(3),
+            time_shift: flag(4),
+        };
+    }
+}
+
+// Presses can land between fixed ticks, so they're held until the next tick consumes them
+#[derive(Resource, Default)]
+pub struct InputLatch {
+    time_shift: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ReplayEventKind {
+    TimeShift,
+    LevelComplete,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ReplayEvent {
+    pub tick: u32,
+    pub kind: ReplayEventKind,
+}
+
+// One run of a level, inputs are stored one byte per fixed tick
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Replay {
+    pub pack: String,
+    pub level: String,
+    // Left out of hand written replays, which skip the level version check
+    #[serde(default)]
+    pub content_hash: Option<u64>,
+    pub tick_hz: f64,
+    pub inputs: Vec<u8>,
+    pub events: Vec<ReplayEvent>,
+}
+impl ConfigTag for Replay {}
+impl Replay {
+    pub fn new(pack: &str, level: &str, content_hash: u64) -> Self {
+        return Self {
+            pack: pack.to_string(),
+            level: level.to_string(),
+            content_hash: Some(content_hash),
+            tick_hz: FIXED_TIMESTEP_HZ,
+            inputs: vec![],
+            events: vec![],
+        };
+    }
+
+    pub fn completion_tick(&self) -> Option<u32> {
+        return self
+            .events
+            .iter()
+            .find(|ev| ev.kind == ReplayEventKind::LevelComplete)
+            .map(|ev| ev.tick);
+    }
+
+    pub fn save(&self) {
+        let dir = user_data_dir().join(REPLAY_DIR_NAME);
+        if let Err(err) = fs::create_dir_all(&dir) {
+            error!("Error creating replay dir, {err}");
+            return;
+        }
+        let name = format!(
+            "{}_{}_{}.json",
+            self.pack.replace(['/', '\\'], "_"),
+            self.level.replace(['/', '\\'], "_"),
+            Local::now().format("%Y%m%d_%H%M%S")
+        );
+        let path = dir.join(name).to_string_lossy().to_string();
+        if let Err(err) = self.save_cfg(&path) {
+            error!("Error saving replay, {err}");
+        }
+    }
+}
+
+pub enum ReplayMode {
+    Record,
+    Playback,
+}
+
+#[derive(Resource)]
+pub struct ReplayState {
+    mode: ReplayMode,
+    replay_path: Option<String>,
+    replay: Option<Replay>,
+    tick: u32,
+}
+impl ReplayState {
+    pub fn new(replay_path: Option<String>) -> Self {
+        return Self {
+            mode: ReplayMode::Record,
+            replay_path,
+            replay: None,
+            tick: 0,
+        };
+    }
+
+    pub fn is_playing(&self) -> bool {
+        return matches!(self.mode, ReplayMode::Playback);
+    }
+
+    pub fn tick(&self) -> u32 {
+        return self.tick;
+    }
+
+    // Time played since the level started, counted in fixed ticks so a replay reproduces it
+    pub fn elapsed_secs(&self) -> f32 {
+        return (self.tick as f64 / FIXED_TIMESTEP_HZ) as f32;
+    }
+}
+
+// Loads the replay passed on the command line and starts its level
+fn start_playback(
+    mut replay_state: ResMut<ReplayState>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+    registry: Res<LevelPackRegistry>,
+    mut level_pack: ResMut<LevelPack>,
+) {
+    let path = match &replay_state.replay_path {
+        Some(path) => path.clone(),
+        None => return,
+    };
+    let replay = match Replay::load_cfg(&path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            let msg = format!("Unable to load replay, {err}");
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+            return;
+        }
+    };
+
+    let level_i = registry.get(&replay.pack).and_then(|pack| {
+        pack.manifest
+            .levels
+            .iter()
+            .position(|level| level.dir == replay.level)
+            .map(|i| (pack, i))
+    });
+    match level_i {
+        Some((pack, i)) => {
+            *level_pack = LevelPack::new(&pack.dir, pack.manifest.clone());
+            level_pack.select_level(i);
+            change_level_ev.send(ChangeLevelEvent::new(0));
+            next_state.set(GameState::InGame);
+            replay_state.mode = ReplayMode::Playback;
+            replay_state.replay = Some(replay);
+        }
+        None => {
+            let msg = format!("Replay level {}/{} not found", replay.pack, replay.level);
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+        }
+    }
+}
+
+// Every level start begins a fresh recording, or rewinds the replay being played back
+pub fn start_replay(
+    mut level_started_ev: EventReader<LevelStartedEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut replay_state: ResMut<ReplayState>,
+) {
+    let ev = match level_started_ev.read().last() {
+        Some(ev) => ev,
+        None => return,
+    };
+    replay_state.tick = 0;
+
+    if let Some(replay) = &replay_state.replay {
+        if replay_state.is_playing() {
+            if replay.pack == ev.pack
+                && replay.level == ev.level
+                && replay
+                    .content_hash
+                    .map_or(true, |hash| hash == ev.content_hash)
+            {
+                return;
+            }
+            let msg = format!(
+                "Replay was recorded on a different version of {}/{}",
+                replay.pack, replay.level
+            );
+            error!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+        }
+    }
+    replay_state.mode = ReplayMode::Record;
+    replay_state.replay = Some(Replay::new(&ev.pack, &ev.level, ev.content_hash));
+}
+
+fn latch_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    mut input_latch: ResMut<InputLatch>,
+) {
+    input_latch.time_shift |= keys.just_pressed(key_binds.time_shift.0);
+}
+
+// A press that no tick consumed before leaving the game mustn't fire once it resumes
+fn clear_input_latch(mut input_latch: ResMut<InputLatch>) {
+    *input_latch = InputLatch::default();
+}
+
+fn gather_player_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    mut input_latch: ResMut<InputLatch>,
+    mut player_input: ResMut<PlayerInput>,
+    mut replay_state: ResMut<ReplayState>,
+) {
+    let tick = replay_state.tick as usize;
+    let is_playing = replay_state.is_playing();
+    let replay = match &mut replay_state.replay {
+        Some(replay) => replay,
+        None => {
+            *player_input = PlayerInput::default();
+            return;
+        }
+    };
+
+    if is_playing {
+        let bits = replay.inputs.get(tick).copied().unwrap_or_default();
+        *player_input = PlayerInput::from_bits(bits);
+        // Anything pressed during playback is dropped rather than replayed later
+        input_latch.time_shift = false;
+    } else {
+        *player_input = PlayerInput {
+            up: keys.pressed(key_binds.up.0),
+            left: keys.pressed(key_binds.left.0),
+            down: keys.pressed(key_binds.down.0),
+            right: keys.pressed(key_binds.right.0),
+            time_shift: mem::take(&mut input_latch.time_shift),
+        };
+        replay.inputs.push(player_input.to_bits());
+    }
+}
+
+// Runs after the tick's gameplay so events are stamped with the tick that caused them
+pub fn update_replay(
+    mut time_shift_ev: EventReader<TimeShiftEvent>,
+    mut level_complete_ev: EventReader<LevelCompleteEvent>,
+    mut error_ev: EventWriter<ErrorEvent>,
+    mut replay_state: ResMut<ReplayState>,
+) {
+    let tick = replay_state.tick;
+    let time_shifts = time_shift_ev.read().count();
+    let completed = level_complete_ev.read().count() > 0;
+    // The tick stops once the run is over so its elapsed time stays at the completion
+    if replay_state.replay.is_none() {
+        return;
+    }
+    replay_state.tick += 1;
+
+    let is_playing = replay_state.is_playing();
+    let replay = match &mut replay_state.replay {
+        Some(replay) => replay,
+        None => return,
+    };
+
+    if is_playing {
+        if completed {
+            let msg = match replay.completion_tick() {
+                Some(recorded) if recorded == tick => {
+                    format!("Replay verified, completed on tick {tick}")
+                }
+                Some(recorded) => {
+                    format!("Replay diverged, completed on tick {tick} instead of {recorded}")
+                }
+                None => format!("Replay diverged, completed on tick {tick} but never did"),
+            };
+            info!("{msg}");
+            error_ev.send(ErrorEvent::new(&msg));
+            replay_state.mode = ReplayMode::Record;
+            replay_state.replay = None;
+        }
+        return;
+    }
+
+    for _ in 0..time_shifts {
+        replay.events.push(ReplayEvent {
+            tick,
+            kind: ReplayEventKind::TimeShift,
+        });
+    }
+    if completed {
+        replay.events.push(ReplayEvent {
+            tick,
+            kind: ReplayEventKind::LevelComplete,
+        });
+        replay.save();
+        replay_state.replay = None;
+    }
+}
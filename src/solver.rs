@@ -0,0 +1,130 @@
+use std::f32::consts::PI;
+
+use crate::{
+    configuration::{level::LevelConfig, player::PlayerConfig, Config},
+    consts::{
+        LEVEL_CFG_PATH, PIXELS_PER_METER, PLAYER_CFG_PATH, SOLVER_MAX_PHASES, SOLVER_MAX_STATES,
+    },
+    level::{
+        blueprints::level::LevelBlueprint,
+        level_pack::LevelPackRegistry,
+        logic_solver::{solve, SolverLimits},
+    },
+};
+
+// Without level dirs every level in every pack is solved
+fn level_dirs() -> Vec<String> {
+    let level_config = LevelConfig::load_or_default(LEVEL_CFG_PATH);
+    let registry = LevelPackRegistry::scan(&level_config.dir, &level_config.packs_dir);
+
+    let mut dirs = vec![];
+    for pack in registry.packs() {
+        for level in pack.manifest.levels.iter() {
+            dirs.push(format!("{}/{}", pack.dir, level.dir));
+        }
+    }
+    return dirs;
+}
+
+// Prints the result for one level, false if one of its doors can never be opened
+fn solve_level(dir: &str, player_weight: f32) -> bool {
+    let blueprint = match LevelBlueprint::load_cfg(dir) {
+        Ok(blueprint) => blueprint,
+        Err(err) => {
+            println!("{dir}: {err}");
+            return false;
+        }
+    };
+    if let Err(err) = blueprint.validate() {
+        println!("{dir}: {err}");
+        return false;
+    }
+
+    let limits = SolverLimits {
+        holders: blueprint.solver_holders(),
+        max_phases: SOLVER_MAX_PHASES,
+        max_states: SOLVER_MAX_STATES,
+    };
+    let report = solve(
+        &blueprint.compiled_logic_graph(),
+        &blueprint.solver_inputs(player_weight),
+        &limits,
+    );
+
+    let solvable = match &report.solution {
+        _ if report.trivial => {
+            println!("{dir}: trivially solvable, every door starts open");
+            true
+        }
+        Some(phases) => {
+            // Anything held beyond the player and the props has to be a past self
+            let needs_time_shift = report.max_held() > limits.holders - blueprint.max_loops();
+            println!(
+                "{dir}: solvable in {} phases{}",
+                phases.len(),
+                match needs_time_shift {
+                    true => ", needs time shift",
+                    false => "",
+                }
+            );
+            for (i, phase) in phases.iter().enumerate() {
+                println!("  phase {}: {}", i + 1, phase.describe());
+            }
+            true
+        }
+        // Only the doors on the way to the goal have to open, which ones that is isn't known here
+        None if report.complete && report.never_opened().is_empty() => {
+            println!("{dir}: the doors are never all open at once, each opens on its own");
+            for (i, phases) in report.output_solutions.iter().enumerate() {
+                let phases = phases
+                    .iter()
+                    .flatten()
+                    .map(|phase| phase.describe())
+                    .collect::<Vec<_>>();
+                println!("  output {i}: {}", phases.join(" | "));
+            }
+            true
+        }
+        None if report.complete => {
+            let never_opened = report
+                .never_opened()
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>();
+            println!(
+                "{dir}: unsolvable, output {} can never open",
+                never_opened.join(", ")
+            );
+            false
+        }
+        None => {
+            println!("{dir}: no solution found within the search limits");
+            true
+        }
+    };
+    for (i, open) in report.stuck_outputs.iter() {
+        let state = match open {
+            true => "open",
+            false => "closed",
+        };
+        println!("  output {i} is always {state}");
+    }
+    return solvable;
+}
+
+// Prints one report per level, true when no level was found unsolvable
+pub fn run_solver(level_dirs_arg: &[String]) -> bool {
+    let dirs = match level_dirs_arg.is_empty() {
+        true => level_dirs(),
+        false => level_dirs_arg.to_vec(),
+    };
+    let player_config = PlayerConfig::load_or_default(PLAYER_CFG_PATH);
+    let radius = player_config.collider_radius / PIXELS_PER_METER;
+    let player_weight = PI * radius * radius;
+
+    let mut all_solvable = true;
+    for dir in dirs.iter() {
+        all_solvable &= solve_level(dir, player_weight);
+    }
+    return all_solvable;
+}
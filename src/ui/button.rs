@@ -1,20 +1,82 @@
 use bevy::{
-    app::Plugin,
+    app::{AppExit, Plugin, Update},
     color::Color,
     prelude::{
-        default, BuildChildren, Bundle, ButtonBundle, ChildBuilder, Component, Entity, TextBundle,
+        default, BackgroundColor, BuildChildren, Bundle, ButtonBundle, Changed, ChildBuilder,
+        Component, Entity, EventWriter, NextState, Query, ResMut, TextBundle, With,
     },
     text::{Text, TextStyle},
-    ui::{AlignItems, JustifyContent, Style, Val},
+    ui::{AlignItems, Interaction, JustifyContent, Style, Val},
+    window::{PrimaryWindow, Window, WindowMode},
 };
 
-use crate::consts::TEXT_SIZE;
+use crate::{
+    consts::{LIGHT, TEXT_SIZE, WHITE},
+    level::level_pack::ChangeLevelEvent,
+};
 
-use super::events::{LoadLevelEvent, LoadLevelPackEvent};
+use super::{
+    events::{LoadLevelEvent, LoadLevelPackEvent},
+    ui_manager::GameState,
+};
 
 pub struct ButtonPlugin;
 impl Plugin for ButtonPlugin {
-    fn build(&self, app: &mut bevy::prelude::App) {}
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Update, (press_buttons, highlight_buttons));
+    }
+}
+
+fn press_buttons(
+    buttons: Query<(&Interaction, &ButtonEvent), Changed<Interaction>>,
+    mut load_level_pack_ev: EventWriter<LoadLevelPackEvent>,
+    mut load_level_ev: EventWriter<LoadLevelEvent>,
+    mut change_level_ev: EventWriter<ChangeLevelEvent>,
+    mut app_exit_ev: EventWriter<AppExit>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for (interaction, button_event) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button_event {
+            ButtonEvent::LevelPack(ev) => {
+                load_level_pack_ev.send(ev.clone());
+                next_state.set(GameState::LevelSelect);
+            }
+            ButtonEvent::Level(ev) => {
+                load_level_ev.send(ev.clone());
+            }
+            ButtonEvent::State(state) => next_state.set(state.clone()),
+            ButtonEvent::Restart => {
+                change_level_ev.send(ChangeLevelEvent::new(0));
+                next_state.set(GameState::InGame);
+            }
+            ButtonEvent::ToggleFullscreen => {
+                if let Ok(mut window) = windows.get_single_mut() {
+                    window.mode = match window.mode {
+                        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+                        _ => WindowMode::Windowed,
+                    };
+                }
+            }
+            ButtonEvent::Quit => {
+                app_exit_ev.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+fn highlight_buttons(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut background_color) in buttons.iter_mut() {
+        background_color.0 = match interaction {
+            Interaction::None => WHITE,
+            Interaction::Hovered | Interaction::Pressed => LIGHT,
+        };
+    }
 }
 
 #[derive(Bundle)]
@@ -35,6 +97,7 @@ impl EventButtonBundle {
                     align_items: AlignItems::Center,
                     ..default()
                 },
+                background_color: WHITE.into(),
                 ..default()
             },
             button_event: event,
@@ -69,6 +132,10 @@ impl EventButtonBundle {
 pub enum ButtonEvent {
     LevelPack(LoadLevelPackEvent),
     Level(LoadLevelEvent),
+    State(GameState),
+    Restart,
+    ToggleFullscreen,
+    Quit,
 }
 impl ButtonEvent {
     pub fn new_level_pack(level_pack_dir: &str) -> Self {
@@ -78,4 +145,20 @@ impl ButtonEvent {
     pub fn new_level(id: usize) -> Self {
         return Self::Level(LoadLevelEvent::new(id));
     }
+
+    pub fn new_state(state: GameState) -> Self {
+        return Self::State(state);
+    }
+
+    pub fn new_restart() -> Self {
+        return Self::Restart;
+    }
+
+    pub fn new_toggle_fullscreen() -> Self {
+        return Self::ToggleFullscreen;
+    }
+
+    pub fn new_quit() -> Self {
+        return Self::Quit;
+    }
 }
@@ -0,0 +1,91 @@
+use bevy::{
+    app::{Plugin, Startup, Update},
+    prelude::{default, Bundle, Commands, Component, Event, EventReader, Query, Res, TextBundle},
+    text::{Text, TextStyle},
+    time::{Time, Timer, TimerMode},
+    ui::{PositionType, Style, Val},
+};
+
+use crate::consts::{ERROR_COLOR, ERROR_DISPLAY_SECS, TEXT_SIZE};
+
+pub struct ErrorDisplayPlugin;
+impl Plugin for ErrorDisplayPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<ErrorEvent>()
+            .add_systems(Startup, spawn_error_display)
+            .add_systems(Update, update_error_display);
+    }
+}
+
+// Shows a message on screen for a few seconds, for problems the game can recover from
+#[derive(Event)]
+pub struct ErrorEvent {
+    pub msg: String,
+}
+impl ErrorEvent {
+    pub fn new(msg: &str) -> Self {
+        return Self {
+            msg: msg.to_string(),
+        };
+    }
+}
+
+#[derive(Component)]
+pub struct ErrorDisplay {
+    timer: Timer,
+}
+
+#[derive(Bundle)]
+pub struct ErrorDisplayBundle {
+    text_bundle: TextBundle,
+    error_display: ErrorDisplay,
+}
+impl ErrorDisplayBundle {
+    pub fn new(font_size: f32) -> Self {
+        return Self {
+            text_bundle: TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size,
+                        color: ERROR_COLOR,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            error_display: ErrorDisplay {
+                timer: Timer::from_seconds(ERROR_DISPLAY_SECS, TimerMode::Once),
+            },
+        };
+    }
+}
+
+fn spawn_error_display(mut commands: Commands) {
+    commands.spawn(ErrorDisplayBundle::new(TEXT_SIZE));
+}
+
+fn update_error_display(
+    time: Res<Time>,
+    mut error_ev: EventReader<ErrorEvent>,
+    mut error_displays: Query<(&mut Text, &mut ErrorDisplay)>,
+) {
+    let msgs = error_ev.read().map(|ev| ev.msg.clone()).collect::<Vec<_>>();
+    for (mut text, mut error_display) in error_displays.iter_mut() {
+        if !msgs.is_empty() {
+            text.sections[0].value = msgs.join("\n");
+            error_display.timer.reset();
+        }
+
+        error_display.timer.tick(time.delta());
+        if error_display.timer.just_finished() {
+            text.sections[0].value.clear();
+        }
+    }
+}
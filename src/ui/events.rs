@@ -8,7 +8,7 @@ impl Plugin for UIEventPlugin {
     }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone)]
 pub struct LoadLevelPackEvent {
     pub dir: String,
 }
@@ -20,7 +20,7 @@ impl LoadLevelPackEvent {
     }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone)]
 pub struct LoadLevelEvent {
     pub id: usize,
 }
@@ -0,0 +1,92 @@
+use bevy::{
+    app::{Plugin, Startup, Update},
+    prelude::{
+        default, state_changed, Bundle, Commands, Component, DetectChanges, IntoSystemConfigs,
+        Query, Res, State, TextBundle, Visibility, With,
+    },
+    text::{Text, TextStyle},
+    ui::{PositionType, Style, Val},
+};
+
+use crate::{
+    consts::{TEXT_COLOR, TEXT_SIZE},
+    level::time_shift::TimeState,
+};
+
+use super::ui_manager::GameState;
+
+pub struct LoopDisplayPlugin;
+impl Plugin for LoopDisplayPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Startup, spawn_loop_display).add_systems(
+            Update,
+            (
+                update_loop_display,
+                update_loop_display_visibility.run_if(state_changed::<GameState>),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct LoopDisplay;
+
+#[derive(Bundle)]
+pub struct LoopDisplayBundle {
+    text_bundle: TextBundle,
+    loop_display: LoopDisplay,
+}
+impl LoopDisplayBundle {
+    pub fn new(font_size: f32) -> Self {
+        return Self {
+            text_bundle: TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            loop_display: LoopDisplay,
+        };
+    }
+}
+
+fn spawn_loop_display(mut commands: Commands) {
+    commands.spawn(LoopDisplayBundle::new(TEXT_SIZE));
+}
+
+fn update_loop_display(
+    time_state: Res<TimeState>,
+    mut loop_displays: Query<&mut Text, With<LoopDisplay>>,
+) {
+    if !time_state.is_changed() {
+        return;
+    }
+    for mut text in loop_displays.iter_mut() {
+        text.sections[0].value = format!("Loop {}/{}", time_state.cur_loop, time_state.max_loops);
+    }
+}
+
+// The loop count only shows while a level is being played or is paused
+fn update_loop_display_visibility(
+    state: Res<State<GameState>>,
+    mut loop_displays: Query<&mut Visibility, With<LoopDisplay>>,
+) {
+    for mut visibility in loop_displays.iter_mut() {
+        *visibility = match state.get() {
+            GameState::InGame | GameState::Paused => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::{
+    prelude::{default, BuildChildren, Commands, Entity, NodeBundle, TextBundle},
+    text::{Text, TextStyle},
+    ui::{AlignItems, FlexDirection, JustifyContent, Style, Val},
+};
+
+use crate::consts::{TEXT_COLOR, TEXT_SIZE};
+
+use super::{
+    button::{ButtonEvent, EventButtonBundle},
+    ui_manager::GameState,
+};
+
+pub fn spawn_main_menu(commands: &mut Commands, root_node: NodeBundle) -> Entity {
+    return commands
+        .spawn(root_node)
+        .with_children(|cb| {
+            cb.spawn(TextBundle {
+                text: Text::from_section(
+                    "time_trial",
+                    TextStyle {
+                        font_size: TEXT_SIZE * 4.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                ..default()
+            });
+            cb.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(30.0),
+                    height: Val::Percent(30.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::SpaceEvenly,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|cb| {
+                EventButtonBundle::new(100.0, 30.0, ButtonEvent::new_state(GameState::PackBrowser))
+                    .spawn(cb, "Play".to_string());
+                EventButtonBundle::new(100.0, 30.0, ButtonEvent::new_quit())
+                    .spawn(cb, "Quit".to_string());
+            });
+        })
+        .id();
+}
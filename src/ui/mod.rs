@@ -1,8 +1,11 @@
 mod button;
-mod events;
+pub mod error_display;
+pub mod events;
+mod loop_display;
 mod main_menu;
+mod pause_menu;
 pub mod selection_list;
-mod stopwatch_display;
+pub mod stopwatch_display;
 mod text;
-mod ui_manager;
+pub mod ui_manager;
 pub mod ui_plugin;
@@ -0,0 +1,112 @@
+use bevy::{
+    prelude::{default, BuildChildren, ChildBuilder, Commands, Entity, NodeBundle, TextBundle},
+    text::{Text, TextStyle},
+    ui::{AlignItems, FlexDirection, JustifyContent, Style, Val},
+};
+
+use crate::{
+    configuration::key_bindings::KeyBinds,
+    consts::{TEXT_COLOR, TEXT_SIZE},
+};
+
+use super::{
+    button::{ButtonEvent, EventButtonBundle},
+    ui_manager::GameState,
+};
+
+pub fn spawn_pause_menu(commands: &mut Commands, root_node: NodeBundle) -> Entity {
+    return commands
+        .spawn(root_node)
+        .with_children(|cb| {
+            spawn_heading(cb, "Paused");
+            spawn_button_column(cb, |cb| {
+                EventButtonBundle::new(100.0, 20.0, ButtonEvent::new_state(GameState::InGame))
+                    .spawn(cb, "Resume".to_string());
+                EventButtonBundle::new(100.0, 20.0, ButtonEvent::new_restart())
+                    .spawn(cb, "Restart".to_string());
+                EventButtonBundle::new(100.0, 20.0, ButtonEvent::new_state(GameState::LevelSelect))
+                    .spawn(cb, "Level Select".to_string());
+                EventButtonBundle::new(100.0, 20.0, ButtonEvent::new_state(GameState::Settings))
+                    .spawn(cb, "Settings".to_string());
+            });
+        })
+        .id();
+}
+
+pub fn spawn_settings_menu(
+    commands: &mut Commands,
+    root_node: NodeBundle,
+    key_binds: &KeyBinds,
+) -> Entity {
+    let controls = [
+        (
+            "Move",
+            format!(
+                "{:?} {:?} {:?} {:?}",
+                key_binds.up.0, key_binds.left.0, key_binds.down.0, key_binds.right.0
+            ),
+        ),
+        ("Time shift", format!("{:?}", key_binds.time_shift.0)),
+        ("Reload level", format!("{:?}", key_binds.reload_level.0)),
+        ("Next level", format!("{:?}", key_binds.next_level.0)),
+        ("Previous level", format!("{:?}", key_binds.prev_level.0)),
+        ("Pause", format!("{:?}", key_binds.pause.0)),
+        ("Level editor", format!("{:?}", key_binds.editor.0)),
+    ];
+
+    return commands
+        .spawn(root_node)
+        .with_children(|cb| {
+            spawn_heading(cb, "Settings");
+            for (action, key) in controls {
+                cb.spawn(TextBundle::from_section(
+                    format!("{action}: {key}"),
+                    TextStyle {
+                        font_size: TEXT_SIZE,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ));
+            }
+            spawn_button_column(cb, |cb| {
+                EventButtonBundle::new(100.0, 40.0, ButtonEvent::new_toggle_fullscreen())
+                    .spawn(cb, "Toggle Fullscreen".to_string());
+                EventButtonBundle::new(100.0, 40.0, ButtonEvent::new_state(GameState::Paused))
+                    .spawn(cb, "Back".to_string());
+            });
+        })
+        .id();
+}
+
+fn spawn_heading(child_builder: &mut ChildBuilder, heading: &str) {
+    child_builder.spawn(TextBundle {
+        text: Text::from_section(
+            heading,
+            TextStyle {
+                font_size: TEXT_SIZE * 3.0,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        ),
+        ..default()
+    });
+}
+
+fn spawn_button_column(
+    child_builder: &mut ChildBuilder,
+    spawn_buttons: impl FnOnce(&mut ChildBuilder),
+) {
+    child_builder
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(30.0),
+                height: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceEvenly,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(spawn_buttons);
+}
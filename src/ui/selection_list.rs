@@ -84,7 +84,9 @@ impl UIListBundle {
         top: f32,
         title: Box<dyn ListItem>,
         items: Vec<Box<dyn ListItem>>,
-    ) {
+    ) -> Entity {
+        // The clipping container is returned so its items can be swapped with update_items
+        let mut list_ent = Entity::PLACEHOLDER;
         child_builder
             .spawn(NodeBundle {
                 style: Style {
@@ -100,40 +102,43 @@ impl UIListBundle {
             })
             .with_children(|mut cb| {
                 title.spawn(&mut cb);
-                cb.spawn(NodeBundle {
-                    style: Style {
-                        flex_direction: FlexDirection::Column,
-                        overflow: Overflow::clip_y(),
-                        justify_content: JustifyContent::SpaceBetween,
-                        align_self: AlignSelf::Stretch,
+                list_ent = cb
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            overflow: Overflow::clip_y(),
+                            justify_content: JustifyContent::SpaceBetween,
+                            align_self: AlignSelf::Stretch,
+                            ..default()
+                        },
+                        background_color: LIGHT.into(),
                         ..default()
-                    },
-                    background_color: LIGHT.into(),
-                    ..default()
-                })
-                .with_children(|cb| {
-                    cb.spawn(Self {
-                        scrolling_list: ScrollingList::default(),
-                        node_bundle: NodeBundle {
-                            style: Style {
-                                flex_direction: FlexDirection::Column,
-                                align_items: AlignItems::Center,
-                                justify_content: JustifyContent::Center,
-                                align_self: AlignSelf::Stretch,
+                    })
+                    .with_children(|cb| {
+                        cb.spawn(Self {
+                            scrolling_list: ScrollingList::default(),
+                            node_bundle: NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    align_self: AlignSelf::Stretch,
+                                    ..default()
+                                },
+                                background_color: PRIMARY.into(),
                                 ..default()
                             },
-                            background_color: PRIMARY.into(),
-                            ..default()
-                        },
-                        accessibility_node: AccessibilityNode(NodeBuilder::new(Role::List)),
+                            accessibility_node: AccessibilityNode(NodeBuilder::new(Role::List)),
+                        })
+                        .with_children(|mut cb| {
+                            for item in items {
+                                item.spawn(&mut cb);
+                            }
+                        });
                     })
-                    .with_children(|mut cb| {
-                        for item in items {
-                            item.spawn(&mut cb);
-                        }
-                    });
-                });
+                    .id();
             });
+        return list_ent;
     }
 
     pub fn update_items(ent: Entity, commands: &mut Commands, items: Vec<Box<dyn ListItem>>) {
@@ -170,28 +175,40 @@ pub trait ListItem {
 
 pub struct LevelPackItem {
     name: ElementType,
+    author: ElementType,
     progress: ElementType,
-    rating: ElementType,
     load_level_pack: ElementType,
 }
 impl LevelPackItem {
-    pub fn new_item(name: &str, progress: f32, rating: f32, level_pack_dir: &str) -> Self {
-        return Self {
-            name: ElementType::Text(name.to_string()),
-            progress: ElementType::Text(format!("{progress}%")),
-            rating: ElementType::Text(format!("{rating}")),
-            load_level_pack: ElementType::Button(
+    pub fn new_item(
+        name: &str,
+        author: &str,
+        progress: f32,
+        num_levels: usize,
+        level_pack_dir: &str,
+    ) -> Self {
+        // An empty pack has nothing to select so it can't be loaded
+        let load_level_pack = match num_levels {
+            0 => ElementType::Text("No levels".to_string()),
+            _ => ElementType::Button(
                 ButtonEvent::new_level_pack(level_pack_dir),
                 "Load Pack".to_string(),
             ),
         };
+
+        return Self {
+            name: ElementType::Text(name.to_string()),
+            author: ElementType::Text(author.to_string()),
+            progress: ElementType::Text(format!("{progress}%")),
+            load_level_pack,
+        };
     }
 
     pub fn new_title() -> Self {
         return Self {
             name: ElementType::Text("Name".to_string()),
+            author: ElementType::Text("Author".to_string()),
             progress: ElementType::Text("Progress".to_string()),
-            rating: ElementType::Text("Rating".to_string()),
             load_level_pack: ElementType::Text("Click to load".to_string()),
         };
     }
@@ -214,9 +231,9 @@ impl ListItem for LevelPackItem {
                 AccessibilityNode(NodeBuilder::new(Role::ListItem)),
             ))
             .with_children(|row| {
-                self.name.spawn(row, 50.0);
+                self.name.spawn(row, 40.0);
+                self.author.spawn(row, 25.0);
                 self.progress.spawn(row, 15.0);
-                self.rating.spawn(row, 15.0);
                 self.load_level_pack.spawn(row, 20.0);
             })
             .id();
@@ -225,21 +242,69 @@ impl ListItem for LevelPackItem {
 
 pub struct LevelItem {
     name: ElementType,
+    par_time: ElementType,
     complete: ElementType,
     load_level: ElementType,
 }
 impl LevelItem {
-    pub fn new(name: &str, complete: bool, level_id: usize) -> Self {
+    pub fn new(
+        name: &str,
+        par_time: Option<f32>,
+        complete: bool,
+        unlocked: bool,
+        level_id: usize,
+    ) -> Self {
+        let par_time = match par_time {
+            Some(secs) => format!("{secs:.1}s"),
+            None => "-".to_string(),
+        };
+        let load_level = match unlocked {
+            true => ElementType::Button(ButtonEvent::new_level(level_id), "Load Level".to_string()),
+            false => ElementType::Text("Locked".to_string()),
+        };
+
         return Self {
             name: ElementType::Text(name.to_string()),
+            par_time: ElementType::Text(par_time),
             complete: ElementType::Text(complete.to_string()),
-            load_level: ElementType::Button(
-                ButtonEvent::new_level(level_id),
-                "Load Level".to_string(),
-            ),
+            load_level,
+        };
+    }
+
+    pub fn new_title() -> Self {
+        return Self {
+            name: ElementType::Text("Name".to_string()),
+            par_time: ElementType::Text("Par".to_string()),
+            complete: ElementType::Text("Complete".to_string()),
+            load_level: ElementType::Text("Click to load".to_string()),
         };
     }
 }
+impl ListItem for LevelItem {
+    fn spawn(self: Box<LevelItem>, child_builder: &mut ChildBuilder) -> Entity {
+        return child_builder
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::SpaceBetween,
+                        width: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: SECONDARY.into(),
+                    ..default()
+                },
+                AccessibilityNode(NodeBuilder::new(Role::ListItem)),
+            ))
+            .with_children(|row| {
+                self.name.spawn(row, 45.0);
+                self.par_time.spawn(row, 15.0);
+                self.complete.spawn(row, 20.0);
+                self.load_level.spawn(row, 20.0);
+            })
+            .id();
+    }
+}
 
 enum ElementType {
     Text(String),
@@ -1,68 +1,94 @@
+use std::time::Duration;
+
 use bevy::{
-    app::{Plugin, Update},
-    color::Color,
-    math::vec3,
-    prelude::{default, Bundle, Component, Event, EventReader, Query, Res, Transform, Visibility},
-    text::{Text, Text2dBundle, TextSection, TextStyle},
-    time::{Stopwatch, Time},
+    app::{Plugin, Startup, Update},
+    prelude::{
+        default, in_state, state_changed, Bundle, Commands, Component, Event, EventReader,
+        IntoSystemConfigs, Query, Res, State, TextBundle, Visibility, With,
+    },
+    text::{Text, TextStyle},
+    ui::{PositionType, Style, Val},
+};
+
+use crate::{
+    consts::{TEXT_COLOR, TEXT_SIZE},
+    level::{goal::LevelCompleteEvent, level_pack::LevelStartedEvent},
+    progress::Progress,
+    replay::{start_replay, ReplayState},
 };
 
-use crate::consts::TEXT_SCALE;
+use super::{events::LoadLevelPackEvent, ui_manager::GameState};
+
 pub struct StopwatchPlugin;
 impl Plugin for StopwatchPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(
-            Update,
-            (
-                reset_stopwatch,
-                update_stopwatch_timer,
-                update_stopwatch_display,
-            ),
-        );
+        app.add_event::<ResetStopwatchEvent>()
+            .add_systems(Startup, spawn_stopwatch)
+            .add_systems(
+                Update,
+                (
+                    update_stopwatch_runs,
+                    update_stopwatch_timer.run_if(in_state(GameState::InGame)),
+                    update_stopwatch_display,
+                    update_stopwatch_visibility.run_if(state_changed::<GameState>),
+                )
+                    .chain()
+                    .after(start_replay),
+            );
     }
 }
 
 #[derive(Bundle)]
 pub struct TimerBundle {
     stopwatch_display: StopwatchDisplay,
-    text_2d_bundle: Text2dBundle,
+    text_bundle: TextBundle,
 }
 impl TimerBundle {
     pub fn new(font_size: f32) -> Self {
         return Self {
             stopwatch_display: StopwatchDisplay::new(),
-            text_2d_bundle: Text2dBundle {
-                text: Text {
-                    sections: vec![TextSection {
-                        value: "".to_string(),
-                        style: TextStyle {
-                            font_size,
-                            color: Color::BLACK,
-                            ..default()
-                        },
-                    }],
-                    ..default()
-                },
-                transform: Transform {
-                    translation: vec3(0.0, 0.0, 0.05),
-                    scale: TEXT_SCALE,
+            text_bundle: TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
                     ..default()
                 },
-                visibility: Visibility::Visible,
+                visibility: Visibility::Hidden,
                 ..default()
             },
         };
     }
 }
 
+// Times the current level against its personal best and keeps a running total for the pack. The
+// time is read from the fixed tick count so it matches what progress records
 #[derive(Component)]
 pub struct StopwatchDisplay {
-    time: Stopwatch,
+    time: Duration,
+    running: bool,
+    best_time: Option<f32>,
+    last_split: Option<(f32, Option<f32>)>,
+    pack: String,
+    pack_time: Duration,
 }
 impl StopwatchDisplay {
     pub fn new() -> Self {
         return Self {
-            time: Stopwatch::new(),
+            time: Duration::ZERO,
+            running: false,
+            best_time: None,
+            last_split: None,
+            pack: String::new(),
+            pack_time: Duration::ZERO,
         };
     }
 }
@@ -70,30 +96,120 @@ impl StopwatchDisplay {
 #[derive(Event)]
 pub struct ResetStopwatchEvent;
 
-pub fn reset_stopwatch(
+fn spawn_stopwatch(mut commands: Commands) {
+    commands.spawn(TimerBundle::new(TEXT_SIZE));
+}
+
+// Completions are handled before resets and starts so the finished level's time is kept
+fn update_stopwatch_runs(
     mut reset_stopwatch_ev: EventReader<ResetStopwatchEvent>,
+    mut level_complete_ev: EventReader<LevelCompleteEvent>,
+    mut level_started_ev: EventReader<LevelStartedEvent>,
+    mut load_level_pack_ev: EventReader<LoadLevelPackEvent>,
     mut stopwatches: Query<&mut StopwatchDisplay>,
+    progress: Res<Progress>,
+    replay_state: Res<ReplayState>,
 ) {
-    if reset_stopwatch_ev.is_empty() {
-        return;
-    }
+    let completed = level_complete_ev.read().count() > 0;
+    let reset = reset_stopwatch_ev.read().count() > 0;
+    let started = level_started_ev.read().last();
+    let pack_loaded = load_level_pack_ev.read().count() > 0;
 
     for mut stopwatch in stopwatches.iter_mut() {
-        stopwatch.time.reset();
+        if completed && stopwatch.running {
+            stopwatch.running = false;
+            let elapsed = Duration::from_secs_f32(replay_state.elapsed_secs());
+            stopwatch.time = elapsed;
+            stopwatch.pack_time += elapsed;
+            stopwatch.last_split = Some((elapsed.as_secs_f32(), stopwatch.best_time));
+        }
+        if reset {
+            stopwatch.time = Duration::ZERO;
+        }
+        if pack_loaded {
+            stopwatch.pack_time = Duration::ZERO;
+        }
+        if let Some(ev) = started {
+            if stopwatch.pack != ev.pack {
+                stopwatch.pack = ev.pack.clone();
+                stopwatch.pack_time = Duration::ZERO;
+            }
+            stopwatch.best_time = progress
+                .level(&ev.pack, &ev.level)
+                .and_then(|level| level.best_time);
+            stopwatch.time = Duration::ZERO;
+            stopwatch.running = true;
+        }
     }
-
-    reset_stopwatch_ev.clear()
 }
 
-pub fn update_stopwatch_timer(time: Res<Time>, mut stopwatches: Query<&mut StopwatchDisplay>) {
+pub fn update_stopwatch_timer(
+    replay_state: Res<ReplayState>,
+    mut stopwatches: Query<&mut StopwatchDisplay>,
+) {
     for mut stopwatch in stopwatches.iter_mut() {
-        stopwatch.time.tick(time.delta());
+        if stopwatch.running {
+            stopwatch.time = Duration::from_secs_f32(replay_state.elapsed_secs());
+        }
     }
 }
 
 pub fn update_stopwatch_display(mut stopwatches: Query<(&mut Text, &StopwatchDisplay)>) {
     for (mut text, stopwatch_display) in stopwatches.iter_mut() {
-        let duration = stopwatch_display.time.elapsed();
-        text.sections[0].value = format!("{}:{}", duration.as_secs(), duration.subsec_millis());
+        let elapsed = stopwatch_display.time;
+        let mut lines = vec![format_duration(elapsed)];
+
+        if let Some(best_time) = stopwatch_display.best_time {
+            lines.push(format!(
+                "PB {} ({})",
+                format_duration(Duration::from_secs_f32(best_time)),
+                format_split(elapsed.as_secs_f32(), best_time)
+            ));
+        }
+        if let Some((time, best_time)) = stopwatch_display.last_split {
+            let split = match best_time {
+                Some(best_time) => format!(" ({})", format_split(time, best_time)),
+                None => String::new(),
+            };
+            lines.push(format!(
+                "Last {}{split}",
+                format_duration(Duration::from_secs_f32(time))
+            ));
+        }
+        // A finished level's time has already been added to the pack total
+        let mut pack_time = stopwatch_display.pack_time;
+        if stopwatch_display.running {
+            pack_time += elapsed;
+        }
+        lines.push(format!("Pack {}", format_duration(pack_time)));
+
+        text.sections[0].value = lines.join("\n");
     }
 }
+
+// The timer only shows while a level is being played or is paused
+fn update_stopwatch_visibility(
+    state: Res<State<GameState>>,
+    mut stopwatches: Query<&mut Visibility, With<StopwatchDisplay>>,
+) {
+    for mut visibility in stopwatches.iter_mut() {
+        *visibility = match state.get() {
+            GameState::InGame | GameState::Paused => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    return format!(
+        "{}:{:02}.{:03}",
+        secs / 60,
+        secs % 60,
+        duration.subsec_millis()
+    );
+}
+
+fn format_split(time: f32, best_time: f32) -> String {
+    return format!("{:+.3}", time - best_time);
+}
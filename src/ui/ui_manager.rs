@@ -1,40 +1,221 @@
 use bevy::{
-    prelude::{default, BuildChildren, Commands, Entity, NodeBundle},
-    ui::{JustifyContent, Style, Val},
+    app::{Plugin, Startup, Update},
+    input::ButtonInput,
+    prelude::{
+        default, in_state, resource_changed, state_changed, AppExtStates, BuildChildren, Commands,
+        Condition, Entity, IntoSystemConfigs, KeyCode, NextState, NodeBundle, OnEnter, Query, Res,
+        ResMut, Resource, State, States, Visibility,
+    },
+    ui::{AlignItems, FlexDirection, JustifyContent, PositionType, Style, UiRect, Val},
 };
 
-use super::selection_list::{LevelPackItem, UIListBundle};
+use bevy_rapier2d::plugin::RapierConfiguration;
 
+use crate::{
+    configuration::key_bindings::KeyBinds,
+    consts::WHITE,
+    level::level_pack::{LevelPack, LevelPackRegistry},
+    progress::Progress,
+};
+
+use super::{
+    button::{ButtonEvent, EventButtonBundle},
+    main_menu::spawn_main_menu,
+    pause_menu::{spawn_pause_menu, spawn_settings_menu},
+    selection_list::{LevelItem, LevelPackItem, ListItem, UIListBundle},
+};
+
+pub struct UIManagerPlugin;
+impl Plugin for UIManagerPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_state::<GameState>()
+            .add_systems(Startup, setup_ui_roots)
+            .add_systems(OnEnter(GameState::PackBrowser), update_pack_list)
+            .add_systems(OnEnter(GameState::LevelSelect), update_level_list)
+            .add_systems(
+                Update,
+                (
+                    (update_ui_visibility, update_physics).run_if(state_changed::<GameState>),
+                    toggle_pause,
+                    update_level_list.run_if(
+                        in_state(GameState::LevelSelect).and_then(resource_changed::<LevelPack>),
+                    ),
+                ),
+            );
+    }
+}
+
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    PackBrowser,
+    LevelSelect,
+    InGame,
+    Paused,
+    Settings,
+    Editor,
+}
+
+// One full screen root per menu screen, only the one matching GameState is visible
+#[derive(Resource)]
 pub struct UIRoots {
     main_menu: Entity,
     level_packs: Entity,
     level_pack: Entity,
-    level: Entity,
+    paused: Entity,
+    settings: Entity,
+    level_packs_list: Entity,
+    level_pack_list: Entity,
 }
 impl UIRoots {
-    pub fn new(commands: &mut Commands) -> Self {
-        let mut me = Self {
-            main_menu: Entity::PLACEHOLDER,
-            level_packs: Entity::PLACEHOLDER,
-            level_pack: Entity::PLACEHOLDER,
-            level: Entity::PLACEHOLDER,
+    pub fn new(commands: &mut Commands, key_binds: &KeyBinds) -> Self {
+        let main_menu = spawn_main_menu(commands, Self::screen_node(Visibility::Visible));
+        let (level_packs, level_packs_list) = Self::spawn_list_screen(
+            commands,
+            Box::new(LevelPackItem::new_title()),
+            GameState::MainMenu,
+        );
+        let (level_pack, level_pack_list) = Self::spawn_list_screen(
+            commands,
+            Box::new(LevelItem::new_title()),
+            GameState::PackBrowser,
+        );
+        let paused = spawn_pause_menu(commands, Self::screen_node(Visibility::Hidden));
+        let settings =
+            spawn_settings_menu(commands, Self::screen_node(Visibility::Hidden), key_binds);
+
+        return Self {
+            main_menu,
+            level_packs,
+            level_pack,
+            paused,
+            settings,
+            level_packs_list,
+            level_pack_list,
         };
-        commands
-            .spawn(NodeBundle {
-                style: Style {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    justify_content: JustifyContent::SpaceBetween,
-                    ..default()
-                },
+    }
+
+    fn screen_node(visibility: Visibility) -> NodeBundle {
+        return NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Percent(2.0)),
                 ..default()
-            })
+            },
+            background_color: WHITE.into(),
+            visibility,
+            ..default()
+        };
+    }
+
+    // Returns the screen root and the list container to fill in when the screen is entered
+    fn spawn_list_screen(
+        commands: &mut Commands,
+        title: Box<dyn ListItem>,
+        back_state: GameState,
+    ) -> (Entity, Entity) {
+        let mut list_ent = Entity::PLACEHOLDER;
+        let root_ent = commands
+            .spawn(Self::screen_node(Visibility::Hidden))
             .with_children(|cb| {
-                // me.level_packs =
-                //     UIListBundle::spawn(cb, Box::new(LevelPackItem::new_title()), vec![]);
-                // me.level_pack = UIListBundle::spawn(cb, vec![]);
-            });
+                list_ent = UIListBundle::spawn(cb, 100.0, 90.0, 0.0, 0.0, title, vec![]);
+                EventButtonBundle::new(20.0, 8.0, ButtonEvent::new_state(back_state))
+                    .spawn(cb, "Back".to_string());
+            })
+            .id();
+        return (root_ent, list_ent);
+    }
+}
+
+fn setup_ui_roots(mut commands: Commands, key_binds: Res<KeyBinds>) {
+    let ui_roots = UIRoots::new(&mut commands, &key_binds);
+    commands.insert_resource(ui_roots);
+}
+
+fn update_ui_visibility(
+    state: Res<State<GameState>>,
+    ui_roots: Res<UIRoots>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    let screens = [
+        (ui_roots.main_menu, GameState::MainMenu),
+        (ui_roots.level_packs, GameState::PackBrowser),
+        (ui_roots.level_pack, GameState::LevelSelect),
+        (ui_roots.paused, GameState::Paused),
+        (ui_roots.settings, GameState::Settings),
+    ];
+    for (ent, screen_state) in screens {
+        if let Ok(mut visibility) = visibilities.get_mut(ent) {
+            *visibility = match *state.get() == screen_state {
+                true => Visibility::Visible,
+                false => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+// The simulation only steps while playing, so pausing and the menus freeze the level
+fn update_physics(state: Res<State<GameState>>, mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = *state.get() == GameState::InGame;
+}
+
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_binds: Res<KeyBinds>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keys.just_pressed(key_binds.pause.0) {
+        return;
+    }
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::InGame),
+        GameState::Settings => next_state.set(GameState::Paused),
+        _ => {}
+    }
+}
+
+fn update_pack_list(
+    mut commands: Commands,
+    ui_roots: Res<UIRoots>,
+    registry: Res<LevelPackRegistry>,
+    progress: Res<Progress>,
+) {
+    let mut items: Vec<Box<dyn ListItem>> = vec![];
+    for pack in registry.packs() {
+        items.push(Box::new(LevelPackItem::new_item(
+            &pack.manifest.title,
+            &pack.manifest.author,
+            progress.pack_percent(&pack.dir, &pack.manifest.levels),
+            pack.manifest.levels.len(),
+            &pack.dir,
+        )));
+    }
+    UIListBundle::update_items(ui_roots.level_packs_list, &mut commands, items);
+}
 
-        return me;
+fn update_level_list(
+    mut commands: Commands,
+    ui_roots: Res<UIRoots>,
+    level_pack: Res<LevelPack>,
+    progress: Res<Progress>,
+) {
+    let mut items: Vec<Box<dyn ListItem>> = vec![];
+    for (i, level) in level_pack.levels().iter().enumerate() {
+        items.push(Box::new(LevelItem::new(
+            level.display_name(),
+            level.par_time,
+            progress.is_complete(level_pack.dir(), &level.dir),
+            level_pack.is_unlocked(i, |dir| progress.is_complete(level_pack.dir(), dir)),
+            i,
+        )));
     }
+    UIListBundle::update_items(ui_roots.level_pack_list, &mut commands, items);
 }
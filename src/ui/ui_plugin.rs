@@ -1,10 +1,22 @@
 use bevy::app::Plugin;
 
-use super::{button::ButtonPlugin, selection_list::ScrollingListPlugin};
+use super::{
+    button::ButtonPlugin, error_display::ErrorDisplayPlugin, events::UIEventPlugin,
+    loop_display::LoopDisplayPlugin, selection_list::ScrollingListPlugin,
+    stopwatch_display::StopwatchPlugin, ui_manager::UIManagerPlugin,
+};
 
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_plugins((ButtonPlugin, ScrollingListPlugin));
+        app.add_plugins((
+            ButtonPlugin,
+            ErrorDisplayPlugin,
+            LoopDisplayPlugin,
+            ScrollingListPlugin,
+            StopwatchPlugin,
+            UIEventPlugin,
+            UIManagerPlugin,
+        ));
     }
 }